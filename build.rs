@@ -0,0 +1,32 @@
+//! 构建脚本
+//!
+//! 启用`web` feature时，将当前的git提交哈希写入环境变量`EASY_MSR_GIT_HASH`，供`/version`接口使用。
+//! 可通过`GIT_HASH`环境变量指定（如在没有`.git`目录的容器中构建时）。
+//! 未启用`web` feature时不调用`git`。
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_HASH");
+    if std::env::var_os("CARGO_FEATURE_WEB").is_none() {
+        return;
+    }
+    for path in [".git/HEAD", ".git/refs/heads", ".git/packed-refs"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+
+    let hash = std::env::var("GIT_HASH").ok().or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "--short=12", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+    });
+    if let Some(hash) = hash.map(|hash| hash.trim().to_string()).filter(|hash| !hash.is_empty()) {
+        println!("cargo:rustc-env=EASY_MSR_GIT_HASH={}", hash);
+    }
+}
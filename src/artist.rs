@@ -0,0 +1,187 @@
+//! # 艺术家索引
+//!
+//! MSR接口中的艺术家只以`Vec<String>`的形式出现在歌曲和专辑数据中，
+//! 本模块将它们聚合为可查询的艺术家索引。
+//!
+//! 艺术家名称会经过规范化后合并：忽略大小写、全角/半角差异以及空白，
+//! 例如`Chen`、`Ｃｈｅｎ`与`Chen `会被视为同一位艺术家。
+
+use crate::dto::{AllAlbumsItem, AllSongsItem, ArtistDetail, ArtistSummary};
+use crate::search::normalize;
+use std::collections::{BTreeMap, HashMap};
+
+/// 生成艺术家名称的合并键
+///
+/// # 示例
+///
+/// ```rust
+/// use easy_msr_api::artist::artist_key;
+///
+/// assert_eq!(artist_key("Ｃｈｅｎ"), artist_key(" chen "));
+/// assert_eq!(artist_key("塞壬唱片 - MSR"), artist_key("塞壬唱片-ＭＳＲ"));
+/// ```
+pub fn artist_key(name: &str) -> String {
+    normalize(name).chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// 索引中的一位艺术家
+#[derive(Debug, Clone, Default)]
+struct ArtistEntry {
+    /// 各种写法及其出现次数
+    variants: BTreeMap<String, usize>,
+
+    /// 参与的专辑在专辑列表中的下标
+    albums: Vec<usize>,
+
+    /// 参与的歌曲在歌曲列表中的下标
+    songs: Vec<usize>,
+}
+
+impl ArtistEntry {
+    /// 出现次数最多的写法，次数相同时取字典序最小的写法
+    fn name(&self) -> String {
+        self.variants
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(name, _)| name.clone())
+            .unwrap_or_default()
+    }
+
+    fn variants(&self) -> Vec<String> {
+        self.variants.keys().cloned().collect()
+    }
+}
+
+/// 记录艺术家的一种写法，返回该艺术家的条目；名称为空时返回`None`
+fn record_variant<'a>(
+    artists: &'a mut BTreeMap<String, ArtistEntry>,
+    name: &str,
+) -> Option<&'a mut ArtistEntry> {
+    let key = artist_key(name);
+    if key.is_empty() {
+        return None;
+    }
+    let entry = artists.entry(key).or_default();
+    *entry.variants.entry(name.trim().to_string()).or_default() += 1;
+    Some(entry)
+}
+
+/// 艺术家索引
+#[derive(Debug, Clone, Default)]
+pub struct ArtistIndex {
+    songs: Vec<AllSongsItem>,
+    albums: Vec<AllAlbumsItem>,
+    artists: BTreeMap<String, ArtistEntry>,
+}
+
+impl ArtistIndex {
+    /// 使用歌曲列表与专辑列表构建艺术家索引
+    ///
+    /// # 参数
+    ///
+    /// * `songs` - 所有歌曲
+    /// * `albums` - 所有专辑
+    pub fn build(songs: Vec<AllSongsItem>, albums: Vec<AllAlbumsItem>) -> Self {
+        let mut artists: BTreeMap<String, ArtistEntry> = BTreeMap::new();
+
+        for (i, album) in albums.iter().enumerate() {
+            for name in &album.artists {
+                if let Some(entry) = record_variant(&mut artists, name)
+                    && entry.albums.last() != Some(&i)
+                {
+                    entry.albums.push(i);
+                }
+            }
+        }
+
+        let album_positions: HashMap<&str, usize> = albums
+            .iter()
+            .enumerate()
+            .map(|(i, album)| (album.id.as_str(), i))
+            .collect();
+        for (i, song) in songs.iter().enumerate() {
+            for name in &song.artists {
+                if let Some(entry) = record_variant(&mut artists, name) {
+                    if entry.songs.last() != Some(&i) {
+                        entry.songs.push(i);
+                    }
+                    // 歌曲的艺术家也视为参与了所属专辑
+                    if let Some(&pos) = album_positions.get(song.album_id.as_str())
+                        && !entry.albums.contains(&pos)
+                    {
+                        entry.albums.push(pos);
+                    }
+                }
+            }
+        }
+
+        for entry in artists.values_mut() {
+            entry.albums.sort_unstable();
+        }
+
+        Self {
+            songs,
+            albums,
+            artists,
+        }
+    }
+
+    /// 艺术家数量
+    pub fn len(&self) -> usize {
+        self.artists.len()
+    }
+
+    /// 索引是否为空
+    pub fn is_empty(&self) -> bool {
+        self.artists.is_empty()
+    }
+
+    /// 列出所有艺术家，按名称排序
+    pub fn list_artists(&self) -> Vec<ArtistSummary> {
+        let mut list: Vec<_> = self
+            .artists
+            .values()
+            .map(|entry| ArtistSummary {
+                name: entry.name(),
+                variants: entry.variants(),
+                album_count: entry.albums.len(),
+                song_count: entry.songs.len(),
+            })
+            .collect();
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+        list
+    }
+
+    /// 获取指定艺术家参与的专辑和歌曲
+    ///
+    /// 名称会先经过规范化，因此任意一种写法都能查到同一位艺术家。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use easy_msr_api::artist::ArtistIndex;
+    /// use easy_msr_api::dto::{AllAlbumsItem, AllSongsItem};
+    ///
+    /// let songs = vec![
+    ///     AllSongsItem { id: "1".into(), album_id: "a".into(), artists: vec!["Chen".into()], ..Default::default() },
+    ///     AllSongsItem { id: "2".into(), album_id: "a".into(), artists: vec!["Ｃｈｅｎ ".into()], ..Default::default() },
+    /// ];
+    /// let albums = vec![AllAlbumsItem { id: "a".into(), artists: vec!["Chen".into()], ..Default::default() }];
+    /// let index = ArtistIndex::build(songs, albums);
+    ///
+    /// assert_eq!(index.len(), 1);
+    /// let artist = index.get_artist("chen").unwrap();
+    /// assert_eq!(artist.name, "Chen");
+    /// assert_eq!(artist.songs.len(), 2);
+    /// assert_eq!(artist.albums.len(), 1);
+    /// ```
+    pub fn get_artist(&self, name: &str) -> Option<ArtistDetail> {
+        let entry = self.artists.get(&artist_key(name))?;
+        Some(ArtistDetail {
+            name: entry.name(),
+            variants: entry.variants(),
+            albums: entry.albums.iter().map(|&i| self.albums[i].clone()).collect(),
+            songs: entry.songs.iter().map(|&i| self.songs[i].clone()).collect(),
+        })
+    }
+}
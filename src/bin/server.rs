@@ -1,29 +1,426 @@
-use easy_msr_api::{client::remote::RemoteApiClient, config::Config, web};
-use tracing::info;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use clap::Parser;
+use easy_msr_api::{
+    client::remote::RemoteApiClient,
+    config::{
+        Config, LogFormat,
+        reload::LiveConfig,
+        settings::{
+            AuthSettings, CacheSettings, CompressionSettings, CorsSettings, LogSettings, RetrySettings,
+            SecurityHeadersSettings, ServerSettings, Settings, UpstreamSettings,
+        },
+    },
+    tasks::Tasks,
+    web,
+};
+use futures::FutureExt;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use tracing_subscriber::{EnvFilter, Registry, layer::SubscriberExt, reload, util::SubscriberInitExt};
+
+/// 配置文件变化后等待的时间，合并编辑器保存时产生的多个事件
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// MSR API代理服务
+/// 
+/// 配置依次从默认值、配置文件、环境变量和命令行参数加载，后者覆盖前者。
+/// 配置文件变化或收到SIGHUP时重新加载配置。
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    /// 配置文件（.toml、.yaml或.yml），未指定时使用CONFIG_FILE环境变量
+    #[arg(short, long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// 输出合并后的配置（TOML格式）后退出
+    #[arg(long)]
+    print_config: bool,
+
+    /// 监听地址，如0.0.0.0或::
+    #[arg(long, value_name = "IP")]
+    bind: Option<IpAddr>,
+
+    /// 监听端口
+    #[arg(short, long)]
+    port: Option<u16>,
+
+    /// 路由前缀，如/msr
+    #[arg(long, value_name = "PREFIX")]
+    path_prefix: Option<String>,
+
+    /// 对外的访问地址（不含路由前缀），如https://msr.example.com
+    #[arg(long, value_name = "URL")]
+    public_url: Option<String>,
+
+    /// 上游地址，可多次指定
+    #[arg(long = "upstream", value_name = "URL")]
+    upstreams: Vec<String>,
+
+    /// 上游请求超时秒数
+    #[arg(long, value_name = "SECS")]
+    upstream_timeout: Option<u64>,
+
+    /// 上游故障时的最多重试次数
+    #[arg(long, value_name = "N")]
+    retries: Option<u32>,
+
+    /// 启用响应缓存
+    #[arg(long)]
+    cache: bool,
+
+    /// 允许的CORS来源，可多次指定，*表示任意来源
+    #[arg(long = "cors-origin", value_name = "ORIGIN")]
+    cors_origins: Vec<String>,
+
+    /// 启用响应压缩（gzip、br、zstd）
+    #[arg(long)]
+    compression: bool,
+
+    /// 启用安全响应头
+    #[arg(long)]
+    security_headers: bool,
+
+    /// API密钥文件，每行一个密钥（名称:密钥），设置后启用API密钥认证
+    #[arg(long, value_name = "FILE")]
+    api_keys_file: Option<PathBuf>,
+
+    /// 日志格式：text或json
+    #[arg(long, value_name = "FORMAT")]
+    log_format: Option<String>,
+
+    /// 日志过滤规则，如info或info,easy_msr_api=debug
+    #[arg(long, value_name = "FILTER")]
+    log_level: Option<String>,
+
+    /// 退出时等待进行中的请求与后台任务的秒数
+    #[arg(long, value_name = "SECS")]
+    shutdown_timeout: Option<u64>,
+}
+
+impl Cli {
+    /// 命令行参数中设置了的项
+    fn settings(&self) -> Settings {
+        Settings {
+            server: ServerSettings {
+                bind: self.bind,
+                port: self.port,
+                path_prefix: self.path_prefix.clone(),
+                public_url: self.public_url.clone(),
+                shutdown_timeout_secs: self.shutdown_timeout,
+                passthrough: None,
+            },
+            upstream: UpstreamSettings {
+                bases: (!self.upstreams.is_empty()).then(|| self.upstreams.clone()),
+                timeout_secs: self.upstream_timeout,
+                ..Default::default()
+            },
+            retry: RetrySettings {
+                max_retries: self.retries,
+                ..Default::default()
+            },
+            cache: CacheSettings {
+                enabled: self.cache.then_some(true),
+                ..Default::default()
+            },
+            cors: CorsSettings {
+                allowed_origins: (!self.cors_origins.is_empty()).then(|| self.cors_origins.clone()),
+                ..Default::default()
+            },
+            compression: CompressionSettings {
+                enabled: self.compression.then_some(true),
+                ..Default::default()
+            },
+            security_headers: SecurityHeadersSettings {
+                enabled: self.security_headers.then_some(true),
+                ..Default::default()
+            },
+            auth: AuthSettings {
+                keys_file: self.api_keys_file.clone(),
+                ..Default::default()
+            },
+            log: LogSettings {
+                format: self.log_format.clone(),
+                level: self.log_level.clone(),
+            },
+            ..Default::default()
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // 初始化日志
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let cli = Cli::parse();
+    let overrides = cli.settings();
+    let settings = Settings::load(cli.config.as_deref(), overrides.clone())?;
+    let cfg = Config::from_settings(settings.clone())?;
+    if cli.print_config {
+        print!("{}", settings.to_toml()?);
+        return Ok(());
+    }
+
+    // 初始化日志与链路导出
+    #[cfg(feature = "otel")]
+    let telemetry = cfg
+        .telemetry
+        .as_ref()
+        .map(|t| easy_msr_api::telemetry::init(&t.otlp_endpoint, &t.service_name))
+        .transpose()?;
+    let json = cfg.log_format == LogFormat::Json;
+    let (filter, filter_handle) = reload::Layer::new(EnvFilter::new(&cfg.log_filter));
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with((!json).then(tracing_subscriber::fmt::layer))
+        .with(json.then(|| tracing_subscriber::fmt::layer().json()));
+    #[cfg(feature = "otel")]
+    let registry = registry.with(telemetry.as_ref().map(|t| t.layer()));
+    registry.init();
+
+    // 日志可能被收集到外部系统，只输出去除了密钥与URL凭据的摘要
+    let summary = cfg.summary();
+    info!("配置加载成功: {:?}", summary);
+    if let Some(endpoint) = &summary.otlp_endpoint {
+        if cfg!(feature = "otel") {
+            info!("链路导出到: {}", endpoint);
+        } else {
+            warn!("未启用otel feature，忽略链路导出配置otel.endpoint");
+        }
+    }
 
-    let cfg = Config::from_env()?;
-    info!("配置加载成功: {:?}", cfg);
+    let tasks = Tasks::new();
+    let mut client = RemoteApiClient::with_upstreams(
+        cfg.upstreams.clone(),
+        cfg.upstream_policy,
+        cfg.upstream_timeout,
+    )
+    .with_tasks(tasks.clone());
+    if let Some(interval) = cfg.health_check_interval {
+        client = client.with_health_check(interval);
+    }
+    if let Some(breaker) = cfg.breaker.clone() {
+        client = client.with_circuit_breaker(breaker);
+    }
+    if let Some(rate_limit) = cfg.rate_limit.clone() {
+        client = client.with_rate_limit(rate_limit);
+    }
+    if let Some(retry) = cfg.retry.clone() {
+        client = client.with_retry(retry);
+    }
+    info!("API客户端创建成功，上游地址: {}", summary.upstreams.join(", "));
+    if let Some(auth) = &cfg.auth {
+        info!("已启用API密钥认证，共{}个密钥", auth.keys.len());
+    }
 
-    let client = RemoteApiClient::new(cfg.remote_base.clone());
-    info!("API客户端创建成功，基础URL: {}", cfg.remote_base);
+    let live = LiveConfig::new(cfg.clone());
+    let reloader = Reloader {
+        file: Settings::config_file(cli.config.as_deref()),
+        overrides,
+        live: live.clone(),
+        client: client.clone(),
+        filter: filter_handle,
+    };
+    tasks.spawn(reloader.run());
 
-    let app = web::routes(client);
-    let listener = tokio::net::TcpListener::bind(&cfg.server_addr()).await?;
-    info!("🚀 服务器启动成功: http://{}", cfg.server_addr());
-    info!("📚 Swagger UI文档: http://{}/swagger-ui", cfg.server_addr());
+    let app = web::routes_with_options(
+        client,
+        web::RouterOptions {
+            passthrough: cfg.passthrough,
+            cache: cfg.cache.clone(),
+            probes: Some(web::ProbeOptions {
+                config: Some(summary),
+                ..Default::default()
+            }),
+            path_prefix: cfg.path_prefix.clone(),
+            public_url: cfg.public_url.clone(),
+            cors: cfg.cors.clone(),
+            compression: cfg.compression.clone(),
+            security_headers: cfg.security_headers.clone(),
+            auth: cfg.auth.clone(),
+            live: Some(live),
+        },
+    );
+    let listener = tokio::net::TcpListener::bind(cfg.socket_addr()).await?;
+    let prefix = cfg.path_prefix.as_deref().unwrap_or_default();
+    info!("🚀 服务器启动成功: http://{}{}", cfg.server_addr(), prefix);
+    info!("📚 Swagger UI文档: http://{}{}/swagger-ui", cfg.server_addr(), prefix);
 
-    axum::serve(listener, app).await?;
+    // 收到退出信号后停止接受新连接，等待进行中的请求完成
+    let signal = async {
+        shutdown_signal().await;
+        Instant::now()
+    }
+    .shared();
+    let server = axum::serve(listener, app).with_graceful_shutdown(signal.clone().map(drop));
+    let drain_timeout = async {
+        signal.clone().await;
+        tokio::time::sleep(cfg.shutdown_timeout).await;
+    };
+    tokio::select! {
+        result = server => result?,
+        _ = drain_timeout => warn!("等待进行中的请求超时（{:?}），中断剩余连接", cfg.shutdown_timeout),
+    }
 
+    // 停止轮询任务，用剩余时间等待后台任务完成
+    let remaining = cfg.shutdown_timeout.saturating_sub(signal.await.elapsed());
+    if tasks.shutdown(remaining).await {
+        info!("后台任务已完成");
+    }
+
+    #[cfg(feature = "otel")]
+    if let Some(telemetry) = telemetry {
+        telemetry.shutdown();
+    }
+
+    info!("服务器已退出");
     Ok(())
 }
+
+/// 等待SIGINT（Ctrl+C）或SIGTERM
+async fn shutdown_signal() {
+    let interrupt = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("监听SIGINT失败: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!("监听SIGTERM失败: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = interrupt => info!("收到SIGINT，开始退出"),
+        _ = terminate => info!("收到SIGTERM，开始退出"),
+    }
+}
+
+/// 在配置文件变化或收到SIGHUP时重新加载配置
+struct Reloader {
+    /// 监听的配置文件
+    file: Option<PathBuf>,
+
+    /// 命令行参数中的配置，重新加载时仍覆盖配置文件与环境变量
+    overrides: Settings,
+
+    live: LiveConfig,
+    client: RemoteApiClient,
+    filter: reload::Handle<EnvFilter, Registry>,
+}
+
+impl Reloader {
+    /// 等待重新加载的信号，服务退出时结束
+    async fn run(self) {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let _watcher = self.file.as_deref().and_then(|file| watch_file(file, tx.clone()));
+        #[cfg(unix)]
+        self.client.tasks().spawn(forward_hangup(tx, self.client.tasks().clone()));
+        #[cfg(not(unix))]
+        drop(tx);
+
+        loop {
+            tokio::select! {
+                Some(()) = rx.recv() => {}
+                _ = self.client.tasks().stopping() => return,
+            }
+            tokio::time::sleep(RELOAD_DEBOUNCE).await;
+            while rx.try_recv().is_ok() {}
+            self.reload();
+        }
+    }
+
+    /// 重新加载配置，并将生效的修改应用到客户端与日志
+    fn reload(&self) {
+        let config = Settings::load(self.file.as_deref(), self.overrides.clone()).and_then(Config::from_settings);
+        let config = match config {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("重新加载配置失败，继续使用当前配置: {}", e);
+                return;
+            }
+        };
+        let reload = self.live.reload(config);
+        let current = self.live.load();
+        if reload.is_applied("upstream.bases") {
+            self.client.set_upstreams(current.upstreams.clone(), current.upstream_policy);
+        }
+        if reload.is_applied("upstream.health_check_secs") {
+            self.client.set_health_check(current.health_check_interval);
+        }
+        if reload.is_applied("rate_limit") {
+            self.client.set_rate_limit(current.rate_limit.clone());
+        }
+        if reload.is_applied("log.level")
+            && let Err(e) = self.filter.reload(EnvFilter::new(&current.log_filter))
+        {
+            warn!("更新日志过滤规则失败: {}", e);
+        }
+        if reload.applied.is_empty() && reload.rejected.is_empty() {
+            info!("配置没有变化");
+        }
+    }
+}
+
+/// 监听配置文件所在的目录，配置文件变化时发送信号
+///
+/// 监听目录而不是文件本身，以便在编辑器先写入临时文件再重命名时也能收到通知。
+fn watch_file(file: &Path, tx: tokio::sync::mpsc::Sender<()>) -> Option<notify::RecommendedWatcher> {
+    use notify::{RecursiveMode, Watcher};
+
+    let name = file.file_name()?.to_owned();
+    let dir = match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if !event.kind.is_access() && event.paths.iter().any(|path| path.file_name() == Some(name.as_os_str())) {
+            let _ = tx.try_send(());
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            warn!("无法监听配置文件{}: {}", file.display(), e);
+            return None;
+        }
+    };
+    if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+        warn!("无法监听配置文件{}: {}", file.display(), e);
+        return None;
+    }
+    info!("监听配置文件{}的变化", file.display());
+    Some(watcher)
+}
+
+/// 收到SIGHUP时发送信号
+#[cfg(unix)]
+async fn forward_hangup(tx: tokio::sync::mpsc::Sender<()>, tasks: Tasks) {
+    let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            warn!("监听SIGHUP失败: {}", e);
+            return;
+        }
+    };
+    loop {
+        tokio::select! {
+            Some(()) = hangup.recv() => {
+                info!("收到SIGHUP，重新加载配置");
+                let _ = tx.try_send(());
+            }
+            _ = tasks.stopping() => return,
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! # 曲库快照
+//!
+//! 提供MSR曲库的本地快照，包含歌曲、专辑和新闻数据。
+//!
+//! 快照可以从远程API拉取，也可以序列化为JSON保存到磁盘后再加载，
+//! 供本地搜索索引等离线功能使用。
+
+use crate::client::remote::RemoteApiClient;
+use crate::dto::{AlbumData, AllAlbumsItem, AllSongsItem, NewsDetailData, NewsItem};
+use crate::error::AppError;
+use futures::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 拉取曲库时的最大并发数
+const CATALOG_CONCURRENCY: usize = 8;
+
+/// 曲库快照
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Catalog {
+    /// 所有歌曲
+    pub songs: Vec<AllSongsItem>,
+
+    /// 所有专辑（包含简介），以专辑cid为键
+    pub albums: HashMap<String, AlbumData>,
+
+    /// 新闻详情（包含正文）
+    pub news: Vec<NewsDetailData>,
+}
+
+impl Catalog {
+    /// 从远程API拉取曲库快照
+    ///
+    /// 会拉取所有歌曲、所有专辑的信息，以及前`news_pages`页新闻的详情。
+    /// 专辑和新闻详情会并发拉取，任意一个请求失败都会导致整体失败。
+    ///
+    /// # 参数
+    ///
+    /// * `client` - 远程API客户端
+    /// * `news_pages` - 拉取的新闻页数，为0时不拉取新闻
+    pub async fn fetch(client: &RemoteApiClient, news_pages: usize) -> Result<Self, AppError> {
+        let songs = client.get_all_songs().await?.data.list;
+
+        let album_ids: Vec<_> = client
+            .get_all_albums()
+            .await?
+            .data
+            .into_iter()
+            .map(|album| album.id)
+            .collect();
+        let albums = stream::iter(album_ids)
+            .map(|id| async move { client.get_album(id.clone()).await.map(|resp| (id, resp.data)) })
+            .buffered(CATALOG_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_, _>>()?;
+
+        let mut news_items: Vec<NewsItem> = Vec::new();
+        let mut last_cid = None;
+        for _ in 0..news_pages {
+            let page = client.get_all_news(last_cid.take()).await?.data;
+            let end = page.end;
+            news_items.extend(page.list);
+            match news_items.last() {
+                Some(last) if !end => last_cid = Some(last.id.clone()),
+                _ => break,
+            }
+        }
+        let news = stream::iter(news_items)
+            .map(|item| async move { client.get_news_detail(item.id).await.map(|resp| resp.data) })
+            .buffered(CATALOG_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            songs,
+            albums,
+            news,
+        })
+    }
+
+    /// 由歌曲列表与专辑列表构建曲库快照
+    ///
+    /// 专辑不包含简介，也不包含新闻；只需要`get_all_songs`与`get_all_albums`两次请求，
+    /// 适合需要频繁刷新的场景。
+    pub fn from_lists(songs: Vec<AllSongsItem>, albums: Vec<AllAlbumsItem>) -> Self {
+        let albums = albums
+            .into_iter()
+            .map(|album| {
+                let data = AlbumData {
+                    id: album.id.clone(),
+                    name: album.name,
+                    cover_url: album.cover_url,
+                    artists: album.artists,
+                    ..Default::default()
+                };
+                (album.id, data)
+            })
+            .collect();
+        Self {
+            songs,
+            albums,
+            news: Vec::new(),
+        }
+    }
+
+    /// 从JSON文件加载曲库快照
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AppError> {
+        let bytes = std::fs::read(path.as_ref())
+            .map_err(|e| AppError::Internal(format!("读取曲库快照失败: {}", e)))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| AppError::Internal(format!("解析曲库快照失败: {}", e)))
+    }
+
+    /// 将曲库快照保存为JSON文件
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AppError> {
+        let bytes = serde_json::to_vec(self)
+            .map_err(|e| AppError::Internal(format!("序列化曲库快照失败: {}", e)))?;
+        std::fs::write(path.as_ref(), bytes)
+            .map_err(|e| AppError::Internal(format!("写入曲库快照失败: {}", e)))
+    }
+
+    /// 根据cid查找专辑
+    pub fn album(&self, id: &str) -> Option<&AlbumData> {
+        self.albums.get(id)
+    }
+}
@@ -5,5 +5,19 @@
 //! ## 模块结构
 //! 
 //! - [`remote`] - 远程API客户端实现，提供完整的API调用功能
+//! - [`coalesce`] - 相同并发请求的合并
+//! - [`breaker`] - 上游故障时快速失败的熔断器
+//! - [`upstream`] - 多上游地址的选择、故障切换与健康检查
+//! - [`limiter`] - 令牌桶限流与并发上限
+//! - [`retry`] - 上游故障时的重试
+//! - [`meta`] - 上游响应的元数据
+//! - [`middleware`] - 出站请求的`tower`中间件
 
+pub mod breaker;
+pub mod coalesce;
+pub mod limiter;
+pub mod meta;
+pub mod middleware;
 pub mod remote;
+pub mod retry;
+pub mod upstream;
@@ -0,0 +1,239 @@
+//! # 熔断器
+//!
+//! 上游MSR API不可用时，继续发出请求只会让每个调用都等到超时。
+//! 熔断器在连续失败达到阈值后进入打开状态，在冷却期内直接拒绝请求（快速失败），
+//! 冷却期结束后进入半开状态，放行少量探测请求：探测全部成功则恢复，任一失败则重新打开。
+//!
+//! 只有连接错误、超时和5xx响应计为失败，4xx响应（如资源不存在）不影响熔断状态。
+
+use crate::error::AppError;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+/// 熔断器配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// 触发熔断的连续失败次数
+    pub failure_threshold: u32,
+
+    /// 熔断打开后的冷却时间
+    pub cooldown: Duration,
+
+    /// 半开状态下放行的探测请求数，全部成功后恢复
+    pub half_open_probes: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+            half_open_probes: 1,
+        }
+    }
+}
+
+/// 熔断器状态
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// 正常放行请求
+    Closed,
+
+    /// 熔断中，直接拒绝请求
+    Open,
+
+    /// 冷却结束，放行探测请求
+    HalfOpen,
+}
+
+/// 熔断器的当前状态
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct CircuitStatus {
+    /// 状态
+    pub state: CircuitState,
+
+    /// 当前连续失败次数
+    #[serde(rename = "consecutiveFailures")]
+    pub consecutive_failures: u32,
+
+    /// 熔断打开时，距离进入半开状态的剩余秒数
+    #[serde(rename = "retryAfter")]
+    pub retry_after: Option<u64>,
+}
+
+/// 熔断器内部状态
+#[derive(Debug)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant, failures: u32 },
+    HalfOpen { in_flight: u32, successes: u32, failures: u32 },
+}
+
+/// 熔断器
+///
+/// 克隆后的实例共享同一份状态。
+#[derive(Clone)]
+pub(crate) struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Arc<Mutex<State>>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Arc::new(Mutex::new(State::Closed { failures: 0 })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 申请发出一次请求
+    ///
+    /// 熔断打开或半开状态的探测名额已满时返回[`AppError::CircuitOpen`]。
+    pub(crate) fn acquire(&self) -> Result<Permit, AppError> {
+        let mut state = self.lock();
+        let now = Instant::now();
+        match &mut *state {
+            State::Closed { .. } => {}
+            State::Open { until, .. } if now < *until => {
+                return Err(AppError::CircuitOpen {
+                    retry_after: *until - now,
+                });
+            }
+            State::Open { failures, .. } => {
+                tracing::info!("熔断冷却结束，进入半开状态");
+                *state = State::HalfOpen {
+                    in_flight: 1,
+                    successes: 0,
+                    failures: *failures,
+                };
+            }
+            State::HalfOpen { in_flight, successes, .. } => {
+                if *in_flight + *successes >= self.config.half_open_probes.max(1) {
+                    return Err(AppError::CircuitOpen {
+                        retry_after: Duration::from_secs(1),
+                    });
+                }
+                *in_flight += 1;
+            }
+        }
+        Ok(Permit {
+            breaker: self.clone(),
+            probe: matches!(*state, State::HalfOpen { .. }),
+            done: false,
+        })
+    }
+
+    fn on_success(&self, probe: bool) {
+        let mut state = self.lock();
+        match &mut *state {
+            State::Closed { failures } => *failures = 0,
+            State::HalfOpen { in_flight, successes, .. } if probe => {
+                *in_flight = in_flight.saturating_sub(1);
+                *successes += 1;
+                if *successes >= self.config.half_open_probes.max(1) {
+                    tracing::info!("探测请求成功，熔断关闭");
+                    *state = State::Closed { failures: 0 };
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_failure(&self, probe: bool) {
+        let mut state = self.lock();
+        let until = Instant::now() + self.config.cooldown;
+        match &mut *state {
+            State::Closed { failures } => {
+                *failures += 1;
+                if *failures >= self.config.failure_threshold.max(1) {
+                    tracing::warn!("上游连续失败{}次，熔断打开", failures);
+                    *state = State::Open {
+                        until,
+                        failures: *failures,
+                    };
+                }
+            }
+            State::HalfOpen { failures, .. } if probe => {
+                tracing::warn!("探测请求失败，熔断重新打开");
+                *state = State::Open {
+                    until,
+                    failures: *failures + 1,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    /// 释放未完成的探测名额
+    fn on_cancel(&self, probe: bool) {
+        if let State::HalfOpen { in_flight, .. } = &mut *self.lock()
+            && probe
+        {
+            *in_flight = in_flight.saturating_sub(1);
+        }
+    }
+
+    /// 当前状态
+    pub(crate) fn status(&self) -> CircuitStatus {
+        let now = Instant::now();
+        match &*self.lock() {
+            State::Closed { failures } => CircuitStatus {
+                state: CircuitState::Closed,
+                consecutive_failures: *failures,
+                retry_after: None,
+            },
+            State::Open { until, failures } if now < *until => CircuitStatus {
+                state: CircuitState::Open,
+                consecutive_failures: *failures,
+                retry_after: Some((*until - now).as_secs_f64().ceil() as u64),
+            },
+            // 冷却已结束，下一个请求会作为探测请求放行
+            State::Open { failures, .. } => CircuitStatus {
+                state: CircuitState::HalfOpen,
+                consecutive_failures: *failures,
+                retry_after: None,
+            },
+            State::HalfOpen { failures, .. } => CircuitStatus {
+                state: CircuitState::HalfOpen,
+                consecutive_failures: *failures,
+                retry_after: None,
+            },
+        }
+    }
+}
+
+/// 一次请求的放行凭证
+///
+/// 请求结束后需调用[`Permit::record`]报告结果；
+/// 未报告就被丢弃（如请求被取消）时不影响熔断状态。
+pub(crate) struct Permit {
+    breaker: CircuitBreaker,
+    probe: bool,
+    done: bool,
+}
+
+impl Permit {
+    /// 报告请求结果
+    pub(crate) fn record<T>(mut self, result: &Result<T, AppError>) {
+        self.done = true;
+        match result {
+            Err(e) if e.is_upstream_failure() => self.breaker.on_failure(self.probe),
+            _ => self.breaker.on_success(self.probe),
+        }
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if !self.done {
+            self.breaker.on_cancel(self.probe);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! # 请求合并
+//!
+//! 将同时发出的相同上游请求（相同的路径与查询参数）合并为一次（single-flight）：
+//! 第一个请求真正访问上游，之后到达的相同请求等待它的结果，不再重复发出。
+//!
+//! 请求完成后立即从进行中的列表移除，因此合并只发生在并发请求之间，不会缓存结果。
+//!
+//! 访问上游的耗时在共享的请求中记录，随结果一起返回给每个调用方，
+//! 被合并的请求在[`time_upstreams`](super::upstream::time_upstreams)中同样计入这段耗时。
+
+use crate::client::upstream;
+use crate::error::AppError;
+use futures::FutureExt;
+use futures::future::{BoxFuture, Shared};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+/// 进行中的请求，其结果与访问上游的耗时可被多个调用方共享
+type InFlight<T> = Shared<BoxFuture<'static, (Result<T, Arc<AppError>>, Duration)>>;
+
+/// 请求合并的统计数据
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+pub struct CoalesceStats {
+    /// 独立执行（未被合并）的请求数
+    pub upstream: u64,
+
+    /// 被合并、未发往上游的请求数
+    pub coalesced: u64,
+}
+
+/// 请求合并器
+///
+/// 克隆后的实例共享进行中的请求与统计数据。
+pub(crate) struct Coalescer<T> {
+    inner: Arc<CoalescerInner<T>>,
+}
+
+struct CoalescerInner<T> {
+    in_flight: Mutex<HashMap<String, InFlight<T>>>,
+    upstream: AtomicU64,
+    coalesced: AtomicU64,
+}
+
+impl<T> Clone for Coalescer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Default for Coalescer<T> {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(CoalescerInner {
+                in_flight: Mutex::new(HashMap::new()),
+                upstream: AtomicU64::new(0),
+                coalesced: AtomicU64::new(0),
+            }),
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Coalescer<T> {
+    /// 执行请求，若已有相同`key`的请求在进行中则等待其结果
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 请求的唯一标识，通常为请求路径与查询参数
+    /// * `fetch` - 实际访问上游的请求
+    ///
+    /// # 返回
+    ///
+    /// 请求结果，以及本次调用是否与进行中的请求合并
+    pub(crate) async fn run<F>(&self, key: String, fetch: F) -> Result<(T, bool), AppError>
+    where
+        F: Future<Output = Result<T, AppError>> + Send + 'static,
+    {
+        let (shared, coalesced) = {
+            let mut in_flight = self.inner.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+            match in_flight.get(&key) {
+                Some(existing) => {
+                    self.inner.coalesced.fetch_add(1, Ordering::Relaxed);
+                    (existing.clone(), true)
+                }
+                None => {
+                    self.inner.upstream.fetch_add(1, Ordering::Relaxed);
+                    // 使用弱引用，避免进行中的请求与合并器互相持有
+                    let owner = Arc::downgrade(&self.inner);
+                    let done_key = key.clone();
+                    let fut = async move {
+                        let (result, elapsed) = upstream::time_upstreams(fetch).await;
+                        finish(&owner, &done_key);
+                        (result.map_err(Arc::new), elapsed)
+                    }
+                    .boxed()
+                    .shared();
+                    in_flight.insert(key, fut.clone());
+                    (fut, false)
+                }
+            }
+        };
+
+        let (result, elapsed) = shared.await;
+        upstream::record_elapsed(elapsed);
+        // 最后一个取得结果的调用方可以拿回原始错误，其余调用方得到共享的错误
+        result
+            .map(|value| (value, coalesced))
+            .map_err(|e| Arc::try_unwrap(e).unwrap_or_else(AppError::Coalesced))
+    }
+
+    /// 当前的统计数据
+    pub(crate) fn stats(&self) -> CoalesceStats {
+        CoalesceStats {
+            upstream: self.inner.upstream.load(Ordering::Relaxed),
+            coalesced: self.inner.coalesced.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 请求完成后将其从进行中的列表移除
+fn finish<T>(owner: &Weak<CoalescerInner<T>>, key: &str) {
+    if let Some(inner) = owner.upgrade() {
+        inner
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+    }
+}
@@ -0,0 +1,160 @@
+//! # 客户端限流
+//!
+//! 批量抓取时请求过快可能导致IP被上游封禁。本模块提供令牌桶限流器：
+//! 每个发往上游的请求需要先取得一个令牌，令牌以固定速率补充，桶的容量即允许的突发请求数。
+//! 同时可以限制同时进行中的请求数。
+//!
+//! 上游返回`429 Too Many Requests`时，限流器会按`Retry-After`暂停发出新请求。
+
+use crate::error::AppError;
+use reqwest::Response;
+use reqwest::header::RETRY_AFTER;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// 上游返回429但没有`Retry-After`时的等待时间
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// `Retry-After`的上限，更长的等待时间按该值处理
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// 限制速率时允许的最小每秒请求数，更小的速率下取得令牌的等待时间过长
+pub const MIN_REQUESTS_PER_SECOND: f64 = 0.001;
+
+/// 限流配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    /// 每秒补充的令牌数，不大于0时不限制速率
+    ///
+    /// 小于[`MIN_REQUESTS_PER_SECOND`]的正数会使等待时间按[`MAX_RETRY_AFTER`]计算。
+    pub requests_per_second: f64,
+
+    /// 令牌桶容量，即允许的突发请求数
+    pub burst: u32,
+
+    /// 同时进行中的请求数上限，为`None`时不限制
+    pub max_concurrency: Option<usize>,
+}
+
+/// 令牌桶
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+    paused_until: Option<Instant>,
+}
+
+struct LimiterInner {
+    config: RateLimitConfig,
+    bucket: Mutex<Bucket>,
+    concurrency: Option<Arc<Semaphore>>,
+}
+
+/// 令牌桶限流器
+///
+/// 克隆后的实例共享同一个令牌桶与并发上限。
+#[derive(Clone)]
+pub(crate) struct RateLimiter {
+    inner: Arc<LimiterInner>,
+}
+
+/// 一次请求的放行凭证，释放时归还并发名额
+pub(crate) struct LimiterPermit {
+    _concurrency: Option<OwnedSemaphorePermit>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: RateLimitConfig) -> Self {
+        Self {
+            inner: Arc::new(LimiterInner {
+                bucket: Mutex::new(Bucket {
+                    tokens: f64::from(config.burst.max(1)),
+                    refilled_at: Instant::now(),
+                    paused_until: None,
+                }),
+                concurrency: config.max_concurrency.map(|n| Arc::new(Semaphore::new(n.max(1)))),
+                config,
+            }),
+        }
+    }
+
+    /// 等待直到可以发出一个请求
+    pub(crate) async fn acquire(&self) -> LimiterPermit {
+        let concurrency = match &self.inner.concurrency {
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        while let Some(wait) = self.try_take() {
+            tokio::time::sleep(wait).await;
+        }
+        LimiterPermit {
+            _concurrency: concurrency,
+        }
+    }
+
+    /// 尝试取得一个令牌，取不到时返回需要等待的时间
+    pub(crate) fn try_take(&self) -> Option<Duration> {
+        let mut bucket = self.inner.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        if let Some(until) = bucket.paused_until {
+            if now < until {
+                return Some(until - now);
+            }
+            bucket.paused_until = None;
+        }
+
+        let rate = self.inner.config.requests_per_second;
+        if rate <= 0.0 {
+            return None;
+        }
+        let capacity = f64::from(self.inner.config.burst.max(1));
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            let wait = Duration::try_from_secs_f64((1.0 - bucket.tokens) / rate).unwrap_or(MAX_RETRY_AFTER);
+            Some(wait.min(MAX_RETRY_AFTER))
+        }
+    }
+
+    /// 在`duration`内暂停发出新请求
+    pub(crate) fn pause(&self, duration: Duration) {
+        let Some(until) = Instant::now().checked_add(duration) else {
+            return;
+        };
+        let mut bucket = self.inner.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        if bucket.paused_until.is_none_or(|current| current < until) {
+            tracing::warn!("上游要求限流，暂停请求{:.1}秒", duration.as_secs_f64());
+            bucket.paused_until = Some(until);
+        }
+    }
+}
+
+/// 解析`429`响应的`Retry-After`响应头，支持秒数与HTTP日期两种格式
+///
+/// 结果不超过[`MAX_RETRY_AFTER`]。
+pub(crate) fn retry_after(response: &Response) -> Duration {
+    let Some(value) = response.headers().get(RETRY_AFTER).and_then(|v| v.to_str().ok()) else {
+        return DEFAULT_RETRY_AFTER;
+    };
+    let wait = match value.trim().parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => chrono::DateTime::parse_from_rfc2822(value.trim())
+            .ok()
+            .and_then(|at| (at.to_utc() - chrono::Utc::now()).to_std().ok())
+            .unwrap_or(DEFAULT_RETRY_AFTER),
+    };
+    wait.min(MAX_RETRY_AFTER)
+}
+
+/// 由`429`响应生成错误
+pub(crate) fn rate_limited(response: &Response) -> AppError {
+    AppError::RateLimited {
+        retry_after: retry_after(response),
+    }
+}
@@ -0,0 +1,94 @@
+//! # 响应元数据
+//!
+//! 普通的API方法只返回反序列化后的数据。`*_with_meta`系列方法额外返回上游响应的元数据，
+//! 包括HTTP状态码、响应头、缓存校验信息（`ETag`、`Last-Modified`）、耗时、
+//! 实际请求的URL以及原始响应体，便于监控与缓存。
+//!
+//! ```rust,no_run
+//! use easy_msr_api::client::remote::RemoteApiClient;
+//!
+//! # async fn demo() -> Result<(), easy_msr_api::error::AppError> {
+//! let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string());
+//! let resp = client.get_album_detail_with_meta("3888".to_string()).await?;
+//! println!("{} 耗时 {:?}，ETag: {:?}", resp.meta.url, resp.meta.latency, resp.meta.etag());
+//! println!("专辑: {}", resp.data.data.name);
+//! # Ok(())
+//! # }
+//! ```
+
+use bytes::Bytes;
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_TYPE, ETAG, HeaderMap, LAST_MODIFIED};
+use std::time::Duration;
+
+/// 上游响应的元数据
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    /// HTTP状态码
+    pub status: StatusCode,
+
+    /// 响应头
+    pub headers: HeaderMap,
+
+    /// 实际请求的完整URL（包含查询参数）
+    pub url: String,
+
+    /// 提供响应的上游地址，不含用户名、密码与查询参数
+    pub upstream: String,
+
+    /// 上游请求耗时，从发出请求到读取完响应体
+    pub latency: Duration,
+
+    /// 是否与其他相同的并发请求合并，共享了同一次上游请求的结果
+    pub coalesced: bool,
+
+    /// 原始响应体
+    pub body: Bytes,
+}
+
+impl ResponseMeta {
+    /// `ETag`响应头
+    pub fn etag(&self) -> Option<&str> {
+        self.header(ETAG.as_str())
+    }
+
+    /// `Last-Modified`响应头
+    pub fn last_modified(&self) -> Option<&str> {
+        self.header(LAST_MODIFIED.as_str())
+    }
+
+    /// `Content-Type`响应头
+    pub fn content_type(&self) -> Option<&str> {
+        self.header(CONTENT_TYPE.as_str())
+    }
+
+    /// 获取指定响应头，不存在或不是有效的字符串时返回`None`
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+}
+
+/// 带有响应元数据的数据
+#[derive(Debug, Clone)]
+pub struct WithMeta<T> {
+    /// 反序列化后的数据
+    pub data: T,
+
+    /// 上游响应的元数据
+    pub meta: ResponseMeta,
+}
+
+impl<T> WithMeta<T> {
+    /// 丢弃元数据，只保留数据
+    pub fn into_data(self) -> T {
+        self.data
+    }
+
+    /// 转换数据，保留元数据
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> WithMeta<U> {
+        WithMeta {
+            data: f(self.data),
+            meta: self.meta,
+        }
+    }
+}
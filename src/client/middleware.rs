@@ -0,0 +1,306 @@
+//! # 出站请求中间件
+//!
+//! `RemoteApiClient`发往上游的每个HTTP请求都经过一个`tower::Service`栈，
+//! 可以通过[`RemoteApiClient::with_layer`](crate::client::remote::RemoteApiClient::with_layer)
+//! 叠加任意`tower::Layer`，用于注入认证头、链路追踪、指标统计、故障注入等，
+//! 也可以组合`tower`生态中的重试、缓存、限流等中间件。
+//!
+//! 本模块提供了几个常用的中间件：
+//!
+//! - [`LoggingLayer`] - 记录每个请求的方法、URL、状态码与耗时
+//! - [`TimingLayer`] - 在每个请求结束后回调耗时等信息
+//! - [`HeaderLayer`] - 为每个请求注入固定的请求头
+//!
+//! 中间件返回的错误若不是`reqwest::Error`或[`AppError`]，会被转换为[`AppError::Middleware`]，
+//! 并与连接错误一样视为上游故障。
+
+use crate::error::AppError;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Method, Request, RequestBuilder, Response, StatusCode};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::util::BoxCloneSyncService;
+use tower::{BoxError, Layer, Service, ServiceExt};
+
+/// 出站请求的服务栈
+///
+/// 请求类型为`reqwest::Request`，响应类型为`reqwest::Response`，错误类型为`tower::BoxError`。
+pub type HttpService = BoxCloneSyncService<Request, Response, BoxError>;
+
+/// 用于构造请求并经过服务栈发送请求的HTTP客户端
+#[derive(Clone)]
+pub(crate) struct HttpClient {
+    client: Client,
+    service: HttpService,
+}
+
+impl HttpClient {
+    pub(crate) fn new(client: Client) -> Self {
+        let service = BoxCloneSyncService::new(client.clone().map_err(BoxError::from));
+        Self { client, service }
+    }
+
+    /// 构造GET请求
+    pub(crate) fn get(&self, url: &str) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    /// 经过服务栈发送请求
+    pub(crate) async fn execute(&self, request: Request) -> Result<Response, AppError> {
+        self.service.clone().oneshot(request).await.map_err(AppError::from)
+    }
+
+    /// 在服务栈的最外层叠加中间件
+    pub(crate) fn layer<L>(&mut self, layer: L)
+    where
+        L: Layer<HttpService>,
+        L::Service: Service<Request, Response = Response, Error = BoxError> + Clone + Send + Sync + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.service = BoxCloneSyncService::new(layer.layer(self.service.clone()));
+    }
+}
+
+/// 记录请求日志的中间件
+///
+/// 成功的请求以`debug`级别记录，失败的请求以`warn`级别记录。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoggingLayer;
+
+impl<S> Layer<S> for LoggingLayer {
+    type Service = Logging<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Logging { inner }
+    }
+}
+
+/// [`LoggingLayer`]生成的服务
+#[derive(Debug, Clone)]
+pub struct Logging<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for Logging<S>
+where
+    S: Service<Request, Response = Response, Error = BoxError>,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Response, BoxError>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let method = request.method().clone();
+        let url = request.url().to_string();
+        let started = Instant::now();
+        let fut = self.inner.call(request);
+        async move {
+            let result = fut.await;
+            match &result {
+                Ok(response) => tracing::debug!(
+                    "上游请求 {} {} -> {} ({:?})",
+                    method,
+                    url,
+                    response.status().as_u16(),
+                    started.elapsed()
+                ),
+                Err(e) => tracing::warn!("上游请求 {} {} 失败 ({:?}): {}", method, url, started.elapsed(), e),
+            }
+            result
+        }
+        .boxed()
+    }
+}
+
+/// 一次请求的耗时信息
+#[derive(Debug, Clone)]
+pub struct RequestTiming {
+    /// 请求方法
+    pub method: Method,
+
+    /// 完整的请求URL
+    pub url: String,
+
+    /// 响应状态码，请求失败时为`None`
+    pub status: Option<StatusCode>,
+
+    /// 从发出请求到收到响应头的耗时
+    pub elapsed: Duration,
+}
+
+/// 请求结束后回调耗时信息的中间件
+///
+/// # 示例
+///
+/// ```rust
+/// use easy_msr_api::client::middleware::TimingLayer;
+/// use easy_msr_api::client::remote::RemoteApiClient;
+///
+/// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string())
+///     .with_layer(TimingLayer::new(|timing| {
+///         println!("{} {:?} {:?}", timing.url, timing.status, timing.elapsed);
+///     }));
+/// ```
+pub struct TimingLayer {
+    callback: Arc<dyn Fn(&RequestTiming) + Send + Sync>,
+}
+
+impl TimingLayer {
+    /// 使用回调函数创建中间件
+    pub fn new(callback: impl Fn(&RequestTiming) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Arc::new(callback),
+        }
+    }
+}
+
+impl Clone for TimingLayer {
+    fn clone(&self) -> Self {
+        Self {
+            callback: self.callback.clone(),
+        }
+    }
+}
+
+impl<S> Layer<S> for TimingLayer {
+    type Service = Timing<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Timing {
+            inner,
+            callback: self.callback.clone(),
+        }
+    }
+}
+
+/// [`TimingLayer`]生成的服务
+pub struct Timing<S> {
+    inner: S,
+    callback: Arc<dyn Fn(&RequestTiming) + Send + Sync>,
+}
+
+impl<S: Clone> Clone for Timing<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            callback: self.callback.clone(),
+        }
+    }
+}
+
+impl<S> Service<Request> for Timing<S>
+where
+    S: Service<Request, Response = Response, Error = BoxError>,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Response, BoxError>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let method = request.method().clone();
+        let url = request.url().to_string();
+        let callback = self.callback.clone();
+        let started = Instant::now();
+        let fut = self.inner.call(request);
+        async move {
+            let result = fut.await;
+            callback(&RequestTiming {
+                method,
+                url,
+                status: result.as_ref().ok().map(Response::status),
+                elapsed: started.elapsed(),
+            });
+            result
+        }
+        .boxed()
+    }
+}
+
+/// 为每个请求注入固定请求头的中间件
+///
+/// 请求中已有同名请求头时会被覆盖。
+///
+/// # 示例
+///
+/// ```rust
+/// use easy_msr_api::client::middleware::HeaderLayer;
+/// use easy_msr_api::client::remote::RemoteApiClient;
+/// use reqwest::header::{AUTHORIZATION, HeaderValue};
+///
+/// let client = RemoteApiClient::new("https://msr-mirror.example.com/api".to_string())
+///     .with_layer(HeaderLayer::new().header(AUTHORIZATION, HeaderValue::from_static("Bearer token")));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HeaderLayer {
+    headers: HeaderMap,
+}
+
+impl HeaderLayer {
+    /// 创建不包含请求头的中间件
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加一个请求头
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+}
+
+impl From<HeaderMap> for HeaderLayer {
+    fn from(headers: HeaderMap) -> Self {
+        Self { headers }
+    }
+}
+
+impl<S> Layer<S> for HeaderLayer {
+    type Service = Headers<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Headers {
+            inner,
+            headers: self.headers.clone(),
+        }
+    }
+}
+
+/// [`HeaderLayer`]生成的服务
+#[derive(Debug, Clone)]
+pub struct Headers<S> {
+    inner: S,
+    headers: HeaderMap,
+}
+
+impl<S> Service<Request> for Headers<S>
+where
+    S: Service<Request, Response = Response, Error = BoxError>,
+{
+    type Response = Response;
+    type Error = BoxError;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        for (name, value) in &self.headers {
+            request.headers_mut().insert(name, value.clone());
+        }
+        self.inner.call(request)
+    }
+}
@@ -5,19 +5,53 @@
 //! 该模块包含`RemoteApiClient`结构体，它封装了HTTP客户端并提供了
 //! 所有MSR API的调用方法。
 
+use crate::artist::ArtistIndex;
+use crate::client::breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitStatus};
+use crate::client::coalesce::{CoalesceStats, Coalescer};
+use crate::client::limiter::{RateLimitConfig, RateLimiter};
+use crate::client::meta::{ResponseMeta, WithMeta};
+use crate::client::middleware::{HttpClient, HttpService};
+use crate::client::retry::{self, RetryConfig};
+use crate::client::upstream::{self, Fetched, Upstream, UpstreamPolicy, UpstreamPool, UpstreamStatus};
 use crate::error::AppError;
+use crate::tasks::Tasks;
 use crate::dto::*;
-use reqwest::{Client, ClientBuilder};
+use arc_swap::ArcSwapOption;
+use futures::{StreamExt, stream};
+use reqwest::{ClientBuilder, Request, Response};
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::sync::Arc;
 use std::time::Duration;
+use tower::{BoxError, Layer, Service};
+
+/// 批量拉取歌曲详情时的最大并发数
+const FAN_OUT_CONCURRENCY: usize = 8;
 
 /// 远程API客户端
 /// 
 /// 封装了HTTP客户端和API基础URL，提供所有MSR API的调用方法。
-/// 支持自定义超时时间和基础URL配置。
+/// 支持自定义超时时间和基础URL配置，也支持配置多个上游地址，
+/// 见[`with_upstreams`](Self::with_upstreams)。
+/// 
+/// 默认会合并相同的并发请求，见[`with_coalescing`](Self::with_coalescing)；
+/// 可通过[`with_circuit_breaker`](Self::with_circuit_breaker)启用熔断，
+/// 通过[`with_rate_limit`](Self::with_rate_limit)启用限流，
+/// 通过[`with_retry`](Self::with_retry)启用上游故障时的重试，
+/// 通过[`with_layer`](Self::with_layer)为出站请求叠加`tower`中间件，
+/// 通过[`with_tasks`](Self::with_tasks)指定后台任务所属的任务组。
+/// 
+/// 上游列表与限流配置可以在运行时通过[`set_upstreams`](Self::set_upstreams)与
+/// [`set_rate_limit`](Self::set_rate_limit)修改，克隆出的客户端同时生效。
 #[derive(Clone)]
 pub struct RemoteApiClient {
-    inner: Client,
-    base: String,
+    inner: HttpClient,
+    upstreams: UpstreamPool,
+    coalescer: Option<Coalescer<Fetched>>,
+    breaker: Option<CircuitBreaker>,
+    limiter: Arc<ArcSwapOption<RateLimiter>>,
+    retry: Option<RetryConfig>,
+    tasks: Tasks,
 }
 
 impl RemoteApiClient {
@@ -59,6 +93,42 @@ impl RemoteApiClient {
     /// );
     /// ```
     pub fn with_config(base: String, timeout: Duration) -> Self {
+        Self::with_upstreams(vec![Upstream::new(base)], UpstreamPolicy::Priority, timeout)
+    }
+
+    /// 使用多个上游地址创建客户端
+    /// 
+    /// 请求按`policy`选择上游，遇到连接错误、超时或5xx响应时自动切换到下一个上游。
+    /// 
+    /// # 参数
+    /// 
+    /// * `upstreams` - 上游地址列表，不能为空
+    /// * `policy` - 上游选择策略
+    /// * `timeout` - HTTP请求超时时间
+    /// 
+    /// # Panics
+    /// 
+    /// `upstreams`为空时panic。
+    /// 
+    /// # 示例
+    /// 
+    /// ```rust
+    /// use std::time::Duration;
+    /// use easy_msr_api::client::remote::RemoteApiClient;
+    /// use easy_msr_api::client::upstream::{Upstream, UpstreamPolicy};
+    /// 
+    /// let client = RemoteApiClient::with_upstreams(
+    ///     vec![
+    ///         Upstream { base: "https://msr-mirror.example.com/api".into(), weight: 3 },
+    ///         Upstream::new("https://monster-siren.hypergryph.com/api"),
+    ///     ],
+    ///     UpstreamPolicy::WeightedRoundRobin,
+    ///     Duration::from_secs(30),
+    /// )
+    /// .with_health_check(Duration::from_secs(30));
+    /// assert_eq!(client.upstream_status().len(), 2);
+    /// ```
+    pub fn with_upstreams(upstreams: Vec<Upstream>, policy: UpstreamPolicy, timeout: Duration) -> Self {
         let client = ClientBuilder::new()
             .timeout(timeout)
             .user_agent(format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")))
@@ -66,11 +136,243 @@ impl RemoteApiClient {
             .expect("Failed to build HTTP client");
         
         Self {
-            inner: client,
-            base: base.trim_end_matches('/').to_string(),
+            inner: HttpClient::new(client),
+            upstreams: UpstreamPool::new(upstreams, policy),
+            coalescer: Some(Coalescer::default()),
+            breaker: None,
+            limiter: Arc::new(ArcSwapOption::empty()),
+            retry: None,
+            tasks: Tasks::default(),
         }
     }
 
+    /// 替换上游地址列表与选择策略
+    /// 
+    /// 修改对所有克隆出的客户端生效，进行中的请求继续使用原来的上游。
+    /// 地址不变的上游保留其健康状态与统计数据。
+    /// 
+    /// # Panics
+    /// 
+    /// `upstreams`为空时panic。
+    /// 
+    /// # 示例
+    /// 
+    /// ```rust
+    /// use easy_msr_api::client::remote::RemoteApiClient;
+    /// use easy_msr_api::client::upstream::{Upstream, UpstreamPolicy};
+    /// 
+    /// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string());
+    /// let shared = client.clone();
+    /// client.set_upstreams(
+    ///     vec![
+    ///         Upstream::new("https://msr-mirror.example.com/api"),
+    ///         Upstream::new("https://monster-siren.hypergryph.com/api"),
+    ///     ],
+    ///     UpstreamPolicy::Priority,
+    /// );
+    /// assert_eq!(shared.upstream_status().len(), 2);
+    /// ```
+    pub fn set_upstreams(&self, upstreams: Vec<Upstream>, policy: UpstreamPolicy) {
+        self.upstreams.reconfigure(upstreams, policy);
+    }
+
+    /// 启用上游的主动健康检查
+    /// 
+    /// 每隔`interval`请求一次各上游的`/fontset`，据此更新上游的健康状态。
+    /// 检查任务在第一次请求时启动，所有克隆出的客户端都被释放或任务组停止后结束。
+    pub fn with_health_check(self, interval: Duration) -> Self {
+        self.set_health_check(Some(interval));
+        self
+    }
+
+    /// 修改主动健康检查的间隔，为`None`时停止检查
+    /// 
+    /// 修改对所有克隆出的客户端生效，已在运行的检查任务在本轮检查后按新的间隔继续。
+    pub fn set_health_check(&self, interval: Option<Duration>) {
+        self.upstreams.set_health_check(interval);
+    }
+
+    /// 各上游的当前状态
+    pub fn upstream_status(&self) -> Vec<UpstreamStatus> {
+        self.upstreams.status()
+    }
+
+    /// 立即检查各上游能否访问
+    /// 
+    /// 并发请求各上游的`/fontset`，据此更新上游的健康状态后返回各上游的状态。
+    pub async fn check_upstreams(&self) -> Vec<UpstreamStatus> {
+        self.upstreams.check(&self.inner).await
+    }
+
+    /// 设置是否合并相同的并发请求
+    /// 
+    /// 启用时（默认），路径与查询参数都相同的请求若在进行中，
+    /// 后到达的请求会等待并共享第一个请求的结果，而不是重复访问上游。
+    /// 
+    /// # 示例
+    /// 
+    /// ```rust
+    /// use easy_msr_api::client::remote::RemoteApiClient;
+    /// 
+    /// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string())
+    ///     .with_coalescing(false);
+    /// assert_eq!(client.coalesce_stats().upstream, 0);
+    /// ```
+    pub fn with_coalescing(mut self, enabled: bool) -> Self {
+        self.coalescer = enabled.then(Coalescer::default);
+        self
+    }
+
+    /// 启用熔断器
+    /// 
+    /// 上游连续失败达到阈值后，冷却期内的请求会直接返回[`AppError::CircuitOpen`]，
+    /// 不再等待超时。克隆出的客户端共享同一个熔断器。
+    /// 
+    /// # 示例
+    /// 
+    /// ```rust
+    /// use std::time::Duration;
+    /// use easy_msr_api::client::breaker::{CircuitBreakerConfig, CircuitState};
+    /// use easy_msr_api::client::remote::RemoteApiClient;
+    /// 
+    /// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string())
+    ///     .with_circuit_breaker(CircuitBreakerConfig {
+    ///         failure_threshold: 3,
+    ///         cooldown: Duration::from_secs(10),
+    ///         half_open_probes: 1,
+    ///     });
+    /// assert_eq!(client.circuit_status().unwrap().state, CircuitState::Closed);
+    /// ```
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.breaker = Some(CircuitBreaker::new(config));
+        self
+    }
+
+    /// 启用客户端限流
+    /// 
+    /// 所有发往上游的请求（包括批量获取、曲库快照等批量操作）都会经过令牌桶限流与并发上限。
+    /// 上游返回`429`时按`Retry-After`暂停所有请求后重试。克隆出的客户端共享同一个限流器。
+    /// 
+    /// # 示例
+    /// 
+    /// ```rust
+    /// use easy_msr_api::client::limiter::RateLimitConfig;
+    /// use easy_msr_api::client::remote::RemoteApiClient;
+    /// 
+    /// // 每秒2个请求，允许5个突发请求，最多4个请求同时进行
+    /// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string())
+    ///     .with_rate_limit(RateLimitConfig {
+    ///         requests_per_second: 2.0,
+    ///         burst: 5,
+    ///         max_concurrency: Some(4),
+    ///     });
+    /// ```
+    pub fn with_rate_limit(mut self, config: RateLimitConfig) -> Self {
+        self.limiter = Arc::new(ArcSwapOption::from_pointee(RateLimiter::new(config)));
+        self
+    }
+
+    /// 修改限流配置，为`None`时不再限流
+    /// 
+    /// 与[`with_rate_limit`](Self::with_rate_limit)不同，修改对所有克隆出的客户端生效。
+    /// 新的限流器从满的令牌桶开始，进行中的请求不受影响。
+    pub fn set_rate_limit(&self, config: Option<RateLimitConfig>) {
+        self.limiter.store(config.map(|config| Arc::new(RateLimiter::new(config))));
+    }
+
+    /// 启用上游故障时的重试
+    /// 
+    /// 所有上游都返回连接错误、超时或5xx响应时，按指数退避等待后重新发起请求，详见[`retry`](crate::client::retry)模块。
+    /// 
+    /// # 示例
+    /// 
+    /// ```rust
+    /// use std::time::Duration;
+    /// use easy_msr_api::client::remote::RemoteApiClient;
+    /// use easy_msr_api::client::retry::RetryConfig;
+    /// 
+    /// // 最多重试3次，分别等待100ms、200ms、400ms
+    /// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string())
+    ///     .with_retry(RetryConfig {
+    ///         max_retries: 3,
+    ///         backoff: Duration::from_millis(100),
+    ///     });
+    /// ```
+    pub fn with_retry(mut self, config: RetryConfig) -> Self {
+        self.retry = Some(config);
+        self
+    }
+
+    /// 为出站请求叠加`tower`中间件
+    /// 
+    /// 中间件包裹每一次实际发出的HTTP请求，包括故障切换时对各上游的尝试、
+    /// 限流重试以及健康检查请求。多次调用时，后添加的中间件位于外层，先处理请求。
+    /// 
+    /// 中间件返回的错误会被还原为`reqwest::Error`或[`AppError`]，
+    /// 其他错误转换为[`AppError::Middleware`]。
+    /// 
+    /// # 示例
+    /// 
+    /// ```rust
+    /// use easy_msr_api::client::middleware::{HeaderLayer, LoggingLayer};
+    /// use easy_msr_api::client::remote::RemoteApiClient;
+    /// use reqwest::header::{HeaderName, HeaderValue};
+    /// 
+    /// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string())
+    ///     .with_layer(HeaderLayer::new().header(
+    ///         HeaderName::from_static("x-client"),
+    ///         HeaderValue::from_static("easy-msr-api"),
+    ///     ))
+    ///     .with_layer(LoggingLayer);
+    /// ```
+    pub fn with_layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<HttpService>,
+        L::Service: Service<Request, Response = Response, Error = BoxError> + Clone + Send + Sync + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.inner.layer(layer);
+        self
+    }
+
+    /// 指定后台任务（健康检查、缓存刷新等）所属的任务组
+    /// 
+    /// 由该客户端创建的路由也在这个任务组中启动后台任务。
+    /// 服务退出前调用[`Tasks::shutdown`]即可停止轮询任务并等待进行中的任务完成。
+    /// 未指定时每个客户端使用独立的任务组。
+    /// 
+    /// # 示例
+    /// 
+    /// ```rust
+    /// use easy_msr_api::client::remote::RemoteApiClient;
+    /// use easy_msr_api::tasks::Tasks;
+    /// 
+    /// let tasks = Tasks::new();
+    /// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string())
+    ///     .with_tasks(tasks.clone());
+    /// ```
+    pub fn with_tasks(mut self, tasks: Tasks) -> Self {
+        self.tasks = tasks;
+        self
+    }
+
+    /// 后台任务所属的任务组
+    pub fn tasks(&self) -> &Tasks {
+        &self.tasks
+    }
+
+    /// 熔断器的当前状态，未启用熔断时返回`None`
+    pub fn circuit_status(&self) -> Option<CircuitStatus> {
+        self.breaker.as_ref().map(CircuitBreaker::status)
+    }
+
+    /// 请求合并的统计数据
+    /// 
+    /// 克隆出的客户端共享同一份统计数据；未启用合并时始终为0。
+    pub fn coalesce_stats(&self) -> CoalesceStats {
+        self.coalescer.as_ref().map(Coalescer::stats).unwrap_or_default()
+    }
+
     /// 统一的请求发送方法，减少代码重复
     /// 
     /// 内部使用的辅助方法，用于发送GET请求并解析响应。
@@ -86,21 +388,96 @@ impl RemoteApiClient {
     /// 
     /// # 返回
     /// 
-    /// 返回解析后的响应数据或错误
-    async fn send_get_request<T>(&self, path: &str, query: &[(&str, &str)]) -> Result<T, AppError>
+    /// 返回解析后的响应数据及响应元数据，或错误
+    async fn send_get_request<T>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<WithMeta<T>, AppError>
     where
         T: serde::de::DeserializeOwned,
     {
-        let url = format!("{}/{}", self.base, path.trim_start_matches('/'));
-        
-        let response = self.inner
-            .get(&url)
-            .query(query)
-            .send()
-            .await?
-            .error_for_status()?;
-            
-        Ok(response.json().await?)
+        let meta = self.fetch(path, query).await?;
+        let data = serde_json::from_slice(&meta.body)?;
+        Ok(WithMeta { data, meta })
+    }
+
+    /// 发送请求并读取响应
+    /// 
+    /// 启用合并时相同的并发请求只访问一次上游；启用熔断时整个请求（含故障切换与重试）计为一次。
+    async fn fetch(&self, path: &str, query: &[(&str, &str)]) -> Result<ResponseMeta, AppError> {
+        #[cfg(feature = "metrics")]
+        let key_path = path;
+        let path = path.trim_start_matches('/').to_string();
+        let query: Vec<(String, String)> = query
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let key = url::form_urlencoded::Serializer::new(format!("{}?", path))
+            .extend_pairs(&query)
+            .finish();
+
+        self.upstreams.ensure_health_check(&self.inner, &self.tasks);
+        let client = self.inner.clone();
+        let upstreams = self.upstreams.clone();
+        let breaker = self.breaker.clone();
+        let limiter = self.limiter.load_full();
+        let retry = self.retry.clone();
+        let fetch = async move {
+            let permit = breaker.as_ref().map(CircuitBreaker::acquire).transpose()?;
+            let result = retry::retry(retry.as_ref(), || upstreams.fetch(&client, limiter.as_deref(), &path, &query)).await;
+            if let Some(permit) = permit {
+                permit.record(&result);
+            }
+            result
+        };
+
+        let (fetched, coalesced) = match &self.coalescer {
+            Some(coalescer) => coalescer.run(key, fetch).await?,
+            None => (fetch.await?, false),
+        };
+        #[cfg(feature = "metrics")]
+        if coalesced {
+            crate::metrics::upstream_coalesced(key_path);
+        }
+        upstream::record_served(&fetched.upstream);
+        Ok(ResponseMeta {
+            status: fetched.status,
+            headers: fetched.headers,
+            url: fetched.url,
+            upstream: fetched.upstream,
+            latency: fetched.latency,
+            coalesced,
+            body: fetched.body,
+        })
+    }
+
+    /// 获取任意上游接口的原始JSON数据
+    /// 
+    /// 不经过DTO转换，保留上游返回的全部字段，适用于本库尚未建模的接口或字段。
+    /// 
+    /// # 参数
+    /// 
+    /// * `path` - API路径（相对于基础URL），如`song/953953`
+    /// * `query` - 查询参数列表
+    /// 
+    /// # 示例
+    /// 
+    /// ```rust,no_run
+    /// use easy_msr_api::client::remote::RemoteApiClient;
+    /// 
+    /// # async fn demo() -> Result<(), easy_msr_api::error::AppError> {
+    /// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string());
+    /// let value = client.get_raw("search/album", &[("keyword", "Wish")]).await?;
+    /// println!("{}", value["data"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[tracing::instrument(name = "get_raw", skip_all, fields(path = %path))]
+    pub async fn get_raw(&self, path: &str, query: &[(&str, &str)]) -> Result<serde_json::Value, AppError> {
+        self.send_get_request(path, query)
+            .await
+            .map(WithMeta::into_data)
     }
 
     /// 获取指定ID的歌曲详情
@@ -113,6 +490,12 @@ impl RemoteApiClient {
     /// 
     /// 返回包含歌曲详细信息的响应
     pub async fn get_song(&self, id: String) -> Result<SongResp, AppError> {
+        self.get_song_with_meta(id).await.map(WithMeta::into_data)
+    }
+
+    /// 与[`get_song`](Self::get_song)相同，同时返回上游响应的元数据
+    #[tracing::instrument(name = "get_song", skip_all, fields(cid = %id))]
+    pub async fn get_song_with_meta(&self, id: String) -> Result<WithMeta<SongResp>, AppError> {
         self.send_get_request(&format!("song/{}", id), &[]).await
     }
 
@@ -122,6 +505,12 @@ impl RemoteApiClient {
     /// 
     /// 返回包含所有歌曲基本信息的列表
     pub async fn get_all_songs(&self) -> Result<AllSongsResp, AppError> {
+        self.get_all_songs_with_meta().await.map(WithMeta::into_data)
+    }
+
+    /// 与[`get_all_songs`](Self::get_all_songs)相同，同时返回上游响应的元数据
+    #[tracing::instrument(name = "get_all_songs", skip_all)]
+    pub async fn get_all_songs_with_meta(&self) -> Result<WithMeta<AllSongsResp>, AppError> {
         self.send_get_request("songs", &[]).await
     }
 
@@ -135,6 +524,12 @@ impl RemoteApiClient {
     /// 
     /// 返回包含专辑详细信息的响应
     pub async fn get_album(&self, id: String) -> Result<AlbumResp, AppError> {
+        self.get_album_with_meta(id).await.map(WithMeta::into_data)
+    }
+
+    /// 与[`get_album`](Self::get_album)相同，同时返回上游响应的元数据
+    #[tracing::instrument(name = "get_album", skip_all, fields(cid = %id))]
+    pub async fn get_album_with_meta(&self, id: String) -> Result<WithMeta<AlbumResp>, AppError> {
         self.send_get_request(&format!("album/{}/data", id), &[]).await
     }
 
@@ -148,15 +543,142 @@ impl RemoteApiClient {
     /// 
     /// 返回包含专辑详情和歌曲列表的响应
     pub async fn get_album_detail(&self, id: String) -> Result<AlbumDetailResp, AppError> {
+        self.get_album_detail_with_meta(id).await.map(WithMeta::into_data)
+    }
+
+    /// 与[`get_album_detail`](Self::get_album_detail)相同，同时返回上游响应的元数据
+    #[tracing::instrument(name = "get_album_detail", skip_all, fields(cid = %id))]
+    pub async fn get_album_detail_with_meta(&self, id: String) -> Result<WithMeta<AlbumDetailResp>, AppError> {
         self.send_get_request(&format!("album/{}/detail", id), &[]).await
     }
 
+    /// 获取完整专辑数据（包含每首歌曲的完整详情）
+    /// 
+    /// 先获取专辑详情，再并发获取其中每首歌曲的详情。
+    /// 单首歌曲获取失败不会导致整体失败，对外展示的错误信息（见[`AppError::client_message`]）
+    /// 记录在对应曲目的`error`字段中，完整的错误记录在日志中。
+    /// 
+    /// # 参数
+    /// 
+    /// * `id` - 专辑的唯一标识符（cid）
+    /// 
+    /// # 返回
+    /// 
+    /// 返回包含专辑信息和所有歌曲详情的响应
+    #[tracing::instrument(name = "get_album_full", skip_all, fields(cid = %id))]
+    pub async fn get_album_full(&self, id: String) -> Result<AlbumFullResp, AppError> {
+        let album = self.get_album_detail(id).await?.data;
+
+        let songs: Vec<AlbumFullTrack> = stream::iter(album.songs)
+            .map(|item| async move {
+                match self.get_song(item.id.clone()).await {
+                    Ok(resp) => AlbumFullTrack {
+                        id: item.id,
+                        song: Some(resp.data),
+                        error: None,
+                    },
+                    Err(e) => {
+                        tracing::warn!("拉取歌曲详情失败 cid={}: {}", item.id, e);
+                        AlbumFullTrack {
+                            id: item.id,
+                            song: None,
+                            error: Some(e.client_message().to_string()),
+                        }
+                    }
+                }
+            })
+            .buffered(FAN_OUT_CONCURRENCY)
+            .collect()
+            .await;
+        let failed = songs.iter().filter(|track| track.error.is_some()).count();
+
+        Ok(ApiResp::success(AlbumFullData {
+            id: album.id,
+            name: album.name,
+            intro: album.intro,
+            belong: album.belong,
+            cover_url: album.cover_url,
+            cover_de_url: album.cover_de_url,
+            songs,
+            failed,
+        }))
+    }
+
+    /// 批量获取歌曲详情
+    /// 
+    /// 重复的cid只会请求一次，请求以有限的并发数发出。
+    /// 单首歌曲获取失败不会导致整体失败，对外展示的错误信息记录在对应条目的`error`字段中。
+    /// 
+    /// # 参数
+    /// 
+    /// * `ids` - 歌曲cid列表
+    /// 
+    /// # 返回
+    /// 
+    /// 返回以歌曲cid为键的查询结果
+    #[tracing::instrument(name = "get_songs_batch", skip_all, fields(count = ids.len()))]
+    pub async fn get_songs_batch(&self, ids: Vec<String>) -> Result<SongBatchResp, AppError> {
+        let data = self
+            .fetch_batch(ids, |id| async move { self.get_song(id).await.map(|resp| resp.data) })
+            .await;
+        Ok(ApiResp::success(data))
+    }
+
+    /// 批量获取专辑信息
+    /// 
+    /// 重复的cid只会请求一次，请求以有限的并发数发出。
+    /// 单个专辑获取失败不会导致整体失败，对外展示的错误信息记录在对应条目的`error`字段中。
+    /// 
+    /// # 参数
+    /// 
+    /// * `ids` - 专辑cid列表
+    /// 
+    /// # 返回
+    /// 
+    /// 返回以专辑cid为键的查询结果
+    #[tracing::instrument(name = "get_albums_batch", skip_all, fields(count = ids.len()))]
+    pub async fn get_albums_batch(&self, ids: Vec<String>) -> Result<AlbumBatchResp, AppError> {
+        let data = self
+            .fetch_batch(ids, |id| async move { self.get_album(id).await.map(|resp| resp.data) })
+            .await;
+        Ok(ApiResp::success(data))
+    }
+
+    /// 对去重后的cid并发执行查询，收集每个cid的结果
+    async fn fetch_batch<T, F, Fut>(&self, ids: Vec<String>, fetch: F) -> BTreeMap<String, BatchEntry<T>>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        let ids: BTreeSet<String> = ids.into_iter().collect();
+        stream::iter(ids)
+            .map(|id| {
+                let result = fetch(id.clone());
+                async move {
+                    let result = result.await.map_err(|e| {
+                        tracing::warn!("批量查询失败 cid={}: {}", id, e);
+                        e.client_message().to_string()
+                    });
+                    (id, BatchEntry::from_result(result))
+                }
+            })
+            .buffer_unordered(FAN_OUT_CONCURRENCY)
+            .collect()
+            .await
+    }
+
     /// 获取所有专辑列表
     /// 
     /// # 返回
     /// 
     /// 返回包含所有专辑基本信息的列表
     pub async fn get_all_albums(&self) -> Result<ApiResp<Vec<AllAlbumsItem>>, AppError> {
+        self.get_all_albums_with_meta().await.map(WithMeta::into_data)
+    }
+
+    /// 与[`get_all_albums`](Self::get_all_albums)相同，同时返回上游响应的元数据
+    #[tracing::instrument(name = "get_all_albums", skip_all)]
+    pub async fn get_all_albums_with_meta(&self) -> Result<WithMeta<ApiResp<Vec<AllAlbumsItem>>>, AppError> {
         self.send_get_request("albums", &[]).await
     }
 
@@ -170,6 +692,12 @@ impl RemoteApiClient {
     /// 
     /// 返回包含新闻列表的响应
     pub async fn get_all_news(&self, last_cid: Option<String>) -> Result<SearchNewsResp, AppError> {
+        self.get_all_news_with_meta(last_cid).await.map(WithMeta::into_data)
+    }
+
+    /// 与[`get_all_news`](Self::get_all_news)相同，同时返回上游响应的元数据
+    #[tracing::instrument(name = "get_all_news", skip_all, fields(last_cid = ?last_cid))]
+    pub async fn get_all_news_with_meta(&self, last_cid: Option<String>) -> Result<WithMeta<SearchNewsResp>, AppError> {
         let mut query = Vec::new();
         if let Some(cid) = &last_cid {
             query.push(("lastCid", cid.as_str()));
@@ -187,6 +715,12 @@ impl RemoteApiClient {
     /// 
     /// 返回包含新闻详细内容的响应
     pub async fn get_news_detail(&self, id: String) -> Result<NewsDetailResp, AppError> {
+        self.get_news_detail_with_meta(id).await.map(WithMeta::into_data)
+    }
+
+    /// 与[`get_news_detail`](Self::get_news_detail)相同，同时返回上游响应的元数据
+    #[tracing::instrument(name = "get_news_detail", skip_all, fields(cid = %id))]
+    pub async fn get_news_detail_with_meta(&self, id: String) -> Result<WithMeta<NewsDetailResp>, AppError> {
         self.send_get_request(&format!("news/{}", id), &[]).await
     }
 
@@ -196,6 +730,12 @@ impl RemoteApiClient {
     /// 
     /// 返回包含字体文件URL配置的响应
     pub async fn get_font(&self) -> Result<FontResp, AppError> {
+        self.get_font_with_meta().await.map(WithMeta::into_data)
+    }
+
+    /// 与[`get_font`](Self::get_font)相同，同时返回上游响应的元数据
+    #[tracing::instrument(name = "get_font", skip_all)]
+    pub async fn get_font_with_meta(&self) -> Result<WithMeta<FontResp>, AppError> {
         self.send_get_request("fontset", &[]).await
     }
 
@@ -209,6 +749,12 @@ impl RemoteApiClient {
     /// 
     /// 返回包含专辑和新闻搜索结果的响应
     pub async fn search(&self, keyword: String) -> Result<SearchResp, AppError> {
+        self.search_with_meta(keyword).await.map(WithMeta::into_data)
+    }
+
+    /// 与[`search`](Self::search)相同，同时返回上游响应的元数据
+    #[tracing::instrument(name = "search", skip_all, fields(keyword = %keyword))]
+    pub async fn search_with_meta(&self, keyword: String) -> Result<WithMeta<SearchResp>, AppError> {
         self.send_get_request("search", &[("keyword", &keyword)]).await
     }
 
@@ -227,6 +773,16 @@ impl RemoteApiClient {
         keyword: String,
         last_cid: Option<String>,
     ) -> Result<SearchAlbumResp, AppError> {
+        self.search_albums_with_meta(keyword, last_cid).await.map(WithMeta::into_data)
+    }
+
+    /// 与[`search_albums`](Self::search_albums)相同，同时返回上游响应的元数据
+    #[tracing::instrument(name = "search_albums", skip_all, fields(keyword = %keyword, last_cid = ?last_cid))]
+    pub async fn search_albums_with_meta(
+        &self,
+        keyword: String,
+        last_cid: Option<String>,
+    ) -> Result<WithMeta<SearchAlbumResp>, AppError> {
         let mut query = vec![("keyword", keyword.as_str())];
         if let Some(cid) = &last_cid {
             query.push(("lastCid", cid.as_str()));
@@ -249,10 +805,60 @@ impl RemoteApiClient {
         keyword: String,
         last_cid: Option<String>,
     ) -> Result<SearchNewsResp, AppError> {
+        self.search_news_with_meta(keyword, last_cid).await.map(WithMeta::into_data)
+    }
+
+    /// 与[`search_news`](Self::search_news)相同，同时返回上游响应的元数据
+    #[tracing::instrument(name = "search_news", skip_all, fields(keyword = %keyword, last_cid = ?last_cid))]
+    pub async fn search_news_with_meta(
+        &self,
+        keyword: String,
+        last_cid: Option<String>,
+    ) -> Result<WithMeta<SearchNewsResp>, AppError> {
         let mut query = vec![("keyword", keyword.as_str())];
         if let Some(cid) = &last_cid {
             query.push(("lastCid", cid.as_str()));
         }
         self.send_get_request("search/news", &query).await
     }
+
+    /// 拉取歌曲与专辑列表并构建艺术家索引
+    /// 
+    /// # 返回
+    /// 
+    /// 返回包含所有艺术家的索引，可多次查询而无需重复请求
+    #[tracing::instrument(name = "artist_index", skip_all)]
+    pub async fn artist_index(&self) -> Result<ArtistIndex, AppError> {
+        let (songs, albums) = futures::try_join!(self.get_all_songs(), self.get_all_albums())?;
+        Ok(ArtistIndex::build(songs.data.list, albums.data))
+    }
+
+    /// 获取所有艺术家列表
+    /// 
+    /// 艺术家名称经过规范化合并，全角/半角与空白不同的写法视为同一位艺术家。
+    /// 
+    /// # 返回
+    /// 
+    /// 返回按名称排序的艺术家列表
+    pub async fn list_artists(&self) -> Result<ArtistListResp, AppError> {
+        Ok(ApiResp::success(self.artist_index().await?.list_artists()))
+    }
+
+    /// 获取指定艺术家参与的专辑和歌曲
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 艺术家名称，任意一种写法均可
+    /// 
+    /// # 返回
+    /// 
+    /// 返回艺术家详情，艺术家不存在时返回`AppError::NotFound`
+    #[tracing::instrument(name = "get_artist", skip_all, fields(artist = %name))]
+    pub async fn get_artist(&self, name: String) -> Result<ArtistResp, AppError> {
+        self.artist_index()
+            .await?
+            .get_artist(&name)
+            .map(ApiResp::success)
+            .ok_or(AppError::NotFound)
+    }
 }
@@ -0,0 +1,61 @@
+//! # 失败重试
+//!
+//! 所有上游都故障时（连接错误、超时、5xx响应），按指数退避等待后重新发起整个请求（含故障切换）。
+//! 4xx响应、响应解析错误和限流不会重试；上游返回`429`的重试由[`limiter`](super::limiter)处理。
+//!
+//! 启用熔断时，一次请求的所有重试只计为一次成功或失败。
+
+use crate::error::AppError;
+use std::future::Future;
+use std::time::Duration;
+
+/// 两次重试之间的最长等待时间
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// 重试配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// 最多重试次数
+    pub max_retries: u32,
+
+    /// 第一次重试前的等待时间，之后每次翻倍（最长30秒）
+    pub backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            backoff: Duration::from_millis(200),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// 第`attempt`次重试（从0开始）前的等待时间
+    fn delay(&self, attempt: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF)
+    }
+}
+
+/// 执行`request`，上游故障时按`config`重试
+pub(crate) async fn retry<T, F, Fut>(config: Option<&RetryConfig>, mut request: F) -> Result<T, AppError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, AppError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match request().await {
+            Err(e) if e.is_upstream_failure() && config.is_some_and(|c| attempt < c.max_retries) => {
+                let delay = config.map(|c| c.delay(attempt)).unwrap_or_default();
+                attempt += 1;
+                tracing::warn!("上游请求失败，{:?}后第{}次重试: {}", delay, attempt, e);
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
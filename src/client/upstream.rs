@@ -0,0 +1,561 @@
+//! # 上游地址池
+//!
+//! 支持同时配置多个上游地址（如官方API与自建镜像），按优先级或加权轮询选择，
+//! 在连接错误、超时或5xx响应时自动切换到下一个上游。
+//!
+//! 失败的上游会被标记为不健康，在一段时间内排到最后尝试；
+//! 启用主动健康检查后，会定期请求各上游的`/fontset`来更新健康状态。
+//!
+//! 上游列表可以在运行时替换，地址不变的上游保留其健康状态与统计数据。
+//!
+//! 每次成功的请求都会记录实际提供响应的上游，可通过[`track_upstreams`]获取；
+//! 访问上游的累计耗时可通过[`time_upstreams`]获取。
+
+use crate::client::limiter::{self, RateLimiter};
+use crate::client::middleware::HttpClient;
+use crate::error::AppError;
+use crate::tasks::Tasks;
+use arc_swap::ArcSwap;
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+use utoipa::ToSchema;
+
+/// 被动标记为不健康的上游，在这段时间后重新参与正常排序
+const PASSIVE_RECOVERY: Duration = Duration::from_secs(30);
+
+/// 健康检查单次请求的超时时间
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 健康检查请求的路径
+const HEALTH_CHECK_PATH: &str = "fontset";
+
+/// 上游返回429时最多重试的次数
+const MAX_RATE_LIMIT_RETRIES: usize = 3;
+
+/// 上游返回429时，`Retry-After`不超过该时长才会等待后重试，否则直接返回错误，并暂停发出新请求该时长
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(30);
+
+tokio::task_local! {
+    static SERVED_BY: RefCell<Vec<String>>;
+    static UPSTREAM_TIME: Cell<Duration>;
+}
+
+/// 执行`fut`，并返回期间提供响应的上游地址（按首次出现的顺序去重）
+///
+/// 只记录与`fut`在同一任务中发出的请求。
+///
+/// # 示例
+///
+/// ```rust,no_run
+/// use easy_msr_api::client::remote::RemoteApiClient;
+/// use easy_msr_api::client::upstream::track_upstreams;
+///
+/// # async fn demo() {
+/// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string());
+/// let (song, upstreams) = track_upstreams(client.get_song("953953".to_string())).await;
+/// println!("{:?} 由 {:?} 提供", song.is_ok(), upstreams);
+/// # }
+/// ```
+pub async fn track_upstreams<F: Future>(fut: F) -> (F::Output, Vec<String>) {
+    SERVED_BY
+        .scope(RefCell::new(Vec::new()), async move {
+            let output = fut.await;
+            (output, SERVED_BY.with(RefCell::take))
+        })
+        .await
+}
+
+/// 执行`fut`，并返回期间访问上游的累计耗时（含故障切换与限流等待）
+///
+/// 只记录与`fut`在同一任务中发出的请求，并发的请求耗时会累加；
+/// 与进行中的相同请求合并时，计入被合并的那次请求的耗时。
+///
+/// # 示例
+///
+/// ```rust,no_run
+/// use easy_msr_api::client::remote::RemoteApiClient;
+/// use easy_msr_api::client::upstream::time_upstreams;
+///
+/// # async fn demo() {
+/// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string());
+/// let (songs, elapsed) = time_upstreams(client.get_all_songs()).await;
+/// println!("{:?} 上游耗时 {:?}", songs.is_ok(), elapsed);
+/// # }
+/// ```
+pub async fn time_upstreams<F: Future>(fut: F) -> (F::Output, Duration) {
+    UPSTREAM_TIME
+        .scope(Cell::new(Duration::ZERO), async move {
+            let output = fut.await;
+            (output, UPSTREAM_TIME.with(Cell::get))
+        })
+        .await
+}
+
+/// 累加访问上游的耗时，不在[`time_upstreams`]中时忽略
+pub(crate) fn record_elapsed(elapsed: Duration) {
+    let _ = UPSTREAM_TIME.try_with(|total| total.set(total.get() + elapsed));
+}
+
+/// 记录提供响应的上游，不在[`track_upstreams`]中时忽略
+pub(crate) fn record_served(base: &str) {
+    let _ = SERVED_BY.try_with(|served| {
+        let mut served = served.borrow_mut();
+        if !served.iter().any(|b| b == base) {
+            served.push(base.to_string());
+        }
+    });
+}
+
+/// 上游地址
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upstream {
+    /// API的基础URL地址
+    pub base: String,
+
+    /// 加权轮询时的权重，为0时只作为备用
+    pub weight: u32,
+}
+
+impl Upstream {
+    /// 创建权重为1的上游地址
+    pub fn new(base: impl Into<String>) -> Self {
+        Self {
+            base: base.into(),
+            weight: 1,
+        }
+    }
+}
+
+/// 上游选择策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpstreamPolicy {
+    /// 按配置顺序，优先使用排在前面的健康上游
+    #[default]
+    Priority,
+
+    /// 在健康的上游之间按权重平滑轮询
+    WeightedRoundRobin,
+}
+
+/// 上游的当前状态
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct UpstreamStatus {
+    /// API的基础URL地址，不含用户名、密码与查询参数
+    pub base: String,
+
+    /// 权重
+    pub weight: u32,
+
+    /// 是否健康
+    pub healthy: bool,
+
+    /// 成功提供响应的次数
+    pub served: u64,
+
+    /// 失败次数（连接错误、超时或5xx响应）
+    pub failures: u64,
+}
+
+/// 池中的一个上游
+struct Node {
+    base: String,
+    /// 用于日志与状态报告的地址，不含用户名、密码与查询参数
+    label: String,
+    weight: u32,
+    healthy: AtomicBool,
+    failed_at: Mutex<Option<Instant>>,
+    served: AtomicU64,
+    failures: AtomicU64,
+}
+
+impl Node {
+    /// 创建上游节点，`previous`为替换前地址相同的节点时沿用其健康状态与统计数据
+    fn new(upstream: Upstream, previous: Option<&Node>) -> Self {
+        let base = upstream.base.trim_end_matches('/').to_string();
+        let label = crate::config::redact_url(&base);
+        let Some(previous) = previous else {
+            return Self {
+                base,
+                label,
+                weight: upstream.weight,
+                healthy: AtomicBool::new(true),
+                failed_at: Mutex::new(None),
+                served: AtomicU64::new(0),
+                failures: AtomicU64::new(0),
+            };
+        };
+        Self {
+            base,
+            label,
+            weight: upstream.weight,
+            healthy: AtomicBool::new(previous.healthy.load(Ordering::Relaxed)),
+            failed_at: Mutex::new(*previous.failed_at.lock().unwrap_or_else(|e| e.into_inner())),
+            served: AtomicU64::new(previous.served.load(Ordering::Relaxed)),
+            failures: AtomicU64::new(previous.failures.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// 是否参与正常排序：健康，或距离上次失败已超过被动恢复时间
+    fn available(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+            || self
+                .failed_at
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .is_none_or(|at| at.elapsed() >= PASSIVE_RECOVERY)
+    }
+
+    fn mark_healthy(&self) {
+        if !self.healthy.swap(true, Ordering::Relaxed) {
+            tracing::info!("上游{}恢复健康", self.label);
+        }
+    }
+
+    fn mark_unhealthy(&self) {
+        *self.failed_at.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+        if self.healthy.swap(false, Ordering::Relaxed) {
+            tracing::warn!("上游{}被标记为不健康", self.label);
+        }
+    }
+}
+
+/// 池中的上游列表，重新配置时整体替换
+struct Members {
+    nodes: Vec<Node>,
+    policy: UpstreamPolicy,
+    /// 平滑加权轮询中每个上游的当前权重
+    current_weights: Mutex<Vec<i64>>,
+}
+
+impl Members {
+    fn new(upstreams: Vec<Upstream>, policy: UpstreamPolicy, previous: &[Node]) -> Self {
+        assert!(!upstreams.is_empty(), "至少需要一个上游地址");
+        let nodes: Vec<Node> = upstreams
+            .into_iter()
+            .map(|upstream| {
+                let base = upstream.base.trim_end_matches('/');
+                let previous = previous.iter().find(|node| node.base == base);
+                Node::new(upstream, previous)
+            })
+            .collect();
+        Self {
+            current_weights: Mutex::new(vec![0; nodes.len()]),
+            nodes,
+            policy,
+        }
+    }
+
+    /// 本次请求尝试上游的顺序：可用的上游按策略排序，不可用的上游排在最后
+    fn order(&self) -> Vec<usize> {
+        let nodes = &self.nodes;
+        let (available, unavailable): (Vec<usize>, Vec<usize>) =
+            (0..nodes.len()).partition(|&i| nodes[i].available());
+
+        let mut order = match self.policy {
+            UpstreamPolicy::Priority => available,
+            UpstreamPolicy::WeightedRoundRobin => match self.pick_weighted(&available) {
+                Some(first) => std::iter::once(first)
+                    .chain(available.into_iter().filter(|&i| i != first))
+                    .collect(),
+                None => available,
+            },
+        };
+        order.extend(unavailable);
+        order
+    }
+
+    /// 平滑加权轮询，从候选上游中选出本次优先使用的一个
+    fn pick_weighted(&self, candidates: &[usize]) -> Option<usize> {
+        let nodes = &self.nodes;
+        let total: i64 = candidates.iter().map(|&i| i64::from(nodes[i].weight)).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut current = self.current_weights.lock().unwrap_or_else(|e| e.into_inner());
+        for &i in candidates {
+            current[i] += i64::from(nodes[i].weight);
+        }
+        let best = candidates.iter().copied().max_by(|&a, &b| current[a].cmp(&current[b]).then(b.cmp(&a)))?;
+        current[best] -= total;
+        Some(best)
+    }
+}
+
+struct PoolInner {
+    members: ArcSwap<Members>,
+    health_check: Mutex<Option<Duration>>,
+    health_check_started: AtomicBool,
+}
+
+impl PoolInner {
+    fn health_check_interval(&self) -> Option<Duration> {
+        *self.health_check.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 上游请求的结果
+#[derive(Debug, Clone)]
+pub(crate) struct Fetched {
+    /// 响应体
+    pub(crate) body: Bytes,
+
+    /// 提供响应的上游地址
+    pub(crate) upstream: String,
+
+    /// 实际请求的完整URL
+    pub(crate) url: String,
+
+    /// HTTP状态码
+    pub(crate) status: StatusCode,
+
+    /// 响应头
+    pub(crate) headers: HeaderMap,
+
+    /// 从发出请求到读取完响应体的耗时
+    pub(crate) latency: Duration,
+}
+
+/// 上游地址池
+///
+/// 克隆后的实例共享上游列表、健康状态与统计数据。
+#[derive(Clone)]
+pub(crate) struct UpstreamPool {
+    inner: Arc<PoolInner>,
+}
+
+impl UpstreamPool {
+    /// 创建地址池
+    ///
+    /// # Panics
+    ///
+    /// `upstreams`为空时panic。
+    pub(crate) fn new(upstreams: Vec<Upstream>, policy: UpstreamPolicy) -> Self {
+        Self {
+            inner: Arc::new(PoolInner {
+                members: ArcSwap::from_pointee(Members::new(upstreams, policy, &[])),
+                health_check: Mutex::new(None),
+                health_check_started: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// 替换上游列表与选择策略
+    ///
+    /// 进行中的请求继续使用原来的列表；地址不变的上游保留其健康状态与统计数据。
+    ///
+    /// # Panics
+    ///
+    /// `upstreams`为空时panic。
+    pub(crate) fn reconfigure(&self, upstreams: Vec<Upstream>, policy: UpstreamPolicy) {
+        let previous = self.inner.members.load();
+        self.inner.members.store(Arc::new(Members::new(upstreams, policy, &previous.nodes)));
+    }
+
+    /// 设置主动健康检查的间隔，为`None`时停止检查
+    ///
+    /// 检查任务在下一次请求时启动，已在运行的任务在本轮检查后按新的间隔继续。
+    pub(crate) fn set_health_check(&self, interval: Option<Duration>) {
+        *self.inner.health_check.lock().unwrap_or_else(|e| e.into_inner()) = interval;
+    }
+
+    /// 各上游的当前状态
+    pub(crate) fn status(&self) -> Vec<UpstreamStatus> {
+        self.inner
+            .members
+            .load()
+            .nodes
+            .iter()
+            .map(|node| UpstreamStatus {
+                base: node.label.clone(),
+                weight: node.weight,
+                healthy: node.healthy.load(Ordering::Relaxed),
+                served: node.served.load(Ordering::Relaxed),
+                failures: node.failures.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    /// 立即检查各上游能否访问，更新健康状态后返回各上游的状态
+    pub(crate) async fn check(&self, client: &HttpClient) -> Vec<UpstreamStatus> {
+        check_nodes(&self.inner.members.load_full().nodes, client).await;
+        self.status()
+    }
+
+    /// 依次尝试各上游，直到得到非故障的响应
+    ///
+    /// 连接错误、超时和5xx响应会切换到下一个上游；4xx响应直接返回。
+    /// 提供了`limiter`时，每个发往上游的请求都需要先通过限流。
+    pub(crate) async fn fetch(
+        &self,
+        client: &HttpClient,
+        limiter: Option<&RateLimiter>,
+        path: &str,
+        query: &[(String, String)],
+    ) -> Result<Fetched, AppError> {
+        let members = self.inner.members.load_full();
+        let mut last_error = None;
+        for i in members.order() {
+            let node = &members.nodes[i];
+            #[cfg(feature = "metrics")]
+            let _in_flight = crate::metrics::upstream_started();
+            let started = Instant::now();
+            let result = request(client, limiter, &format!("{}/{}", node.base, path), query).await;
+            record_elapsed(started.elapsed());
+            #[cfg(feature = "metrics")]
+            crate::metrics::upstream_finished(path, &result, started.elapsed());
+
+            match result {
+                Ok(mut fetched) => {
+                    node.mark_healthy();
+                    node.served.fetch_add(1, Ordering::Relaxed);
+                    fetched.upstream = node.label.clone();
+                    return Ok(fetched);
+                }
+                Err(e) if e.is_upstream_failure() => {
+                    tracing::warn!("上游{}请求失败: {}", node.label, e);
+                    node.failures.fetch_add(1, Ordering::Relaxed);
+                    node.mark_unhealthy();
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| AppError::Internal("没有可用的上游地址".into())))
+    }
+
+    /// 在`tasks`中启动主动健康检查任务（只启动一次）
+    pub(crate) fn ensure_health_check(&self, client: &HttpClient, tasks: &Tasks) {
+        if self.inner.health_check_interval().is_none()
+            || self.inner.health_check_started.swap(true, Ordering::AcqRel)
+        {
+            return;
+        }
+        tasks.spawn(health_check_loop(Arc::downgrade(&self.inner), client.clone(), tasks.clone()));
+    }
+}
+
+/// 向单个上游发出请求，返回结果中的`upstream`由调用方填写
+///
+/// 上游返回429时按`Retry-After`暂停限流器并重试；未启用限流或等待时间过长时返回[`AppError::RateLimited`]。
+async fn request(
+    client: &HttpClient,
+    limiter: Option<&RateLimiter>,
+    url: &str,
+    query: &[(String, String)],
+) -> Result<Fetched, AppError> {
+    let mut attempt = 0;
+    loop {
+        let _permit = match limiter {
+            Some(limiter) => Some(limiter.acquire().await),
+            None => None,
+        };
+        let started = Instant::now();
+        let span = tracing::info_span!(
+            "upstream_request",
+            otel.kind = "client",
+            http.request.method = "GET",
+            url.full = crate::config::redact_url(url),
+            http.response.status_code = tracing::field::Empty,
+            otel.status_code = tracing::field::Empty,
+        );
+        let response = async {
+            let request = client.get(url).query(query).build()?;
+            #[cfg(feature = "otel")]
+            let request = crate::telemetry::with_context(request);
+            let result = client.execute(request).await;
+            let span = tracing::Span::current();
+            match &result {
+                Ok(response) => {
+                    span.record("http.response.status_code", response.status().as_u16());
+                    if response.status().is_server_error() {
+                        span.record("otel.status_code", "error");
+                    }
+                }
+                Err(_) => {
+                    span.record("otel.status_code", "error");
+                }
+            }
+            result
+        }
+        .instrument(span)
+        .await?;
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            let wait = limiter::retry_after(&response);
+            match limiter {
+                Some(limiter) if attempt < MAX_RATE_LIMIT_RETRIES && wait <= MAX_RATE_LIMIT_WAIT => {
+                    limiter.pause(wait);
+                    attempt += 1;
+                    continue;
+                }
+                // 等待时间过长时直接返回错误，最多暂停MAX_RATE_LIMIT_WAIT
+                Some(limiter) => limiter.pause(wait.min(MAX_RATE_LIMIT_WAIT)),
+                None => {}
+            }
+            return Err(limiter::rate_limited(&response));
+        }
+        let response = response.error_for_status()?;
+        let url = response.url().to_string();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        return Ok(Fetched {
+            body,
+            upstream: String::new(),
+            url,
+            status,
+            headers,
+            latency: started.elapsed(),
+        });
+    }
+}
+
+/// 定期检查各上游的健康状态，所有客户端都被释放、关闭健康检查或任务组停止后退出
+async fn health_check_loop(pool: Weak<PoolInner>, client: HttpClient, tasks: Tasks) {
+    loop {
+        let Some(pool) = pool.upgrade() else {
+            return;
+        };
+        let Some(interval) = pool.health_check_interval() else {
+            pool.health_check_started.store(false, Ordering::Release);
+            return;
+        };
+        check_nodes(&pool.members.load_full().nodes, &client).await;
+        drop(pool);
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tasks.stopping() => return,
+        }
+    }
+}
+
+/// 并发检查各上游能否访问，并更新健康状态
+async fn check_nodes(nodes: &[Node], client: &HttpClient) {
+    futures::future::join_all(nodes.iter().map(|node| async move {
+        let request = client
+            .get(&format!("{}/{}", node.base, HEALTH_CHECK_PATH))
+            .timeout(HEALTH_CHECK_TIMEOUT)
+            .build();
+        let healthy = match request {
+            Ok(request) => client
+                .execute(request)
+                .await
+                .is_ok_and(|response| response.status().is_success()),
+            Err(_) => false,
+        };
+        if healthy {
+            node.mark_healthy();
+        } else {
+            node.mark_unhealthy();
+        }
+    }))
+    .await;
+}
@@ -0,0 +1,155 @@
+//! # 配置热加载
+//!
+//! [`LiveConfig`]保存当前生效的配置，由Web路由（响应缓存与CORS）与服务共享。
+//! 通过[`LiveConfig::reload`]替换配置时，只有可以在运行中生效的配置项会被替换：
+//!
+//! - 响应缓存策略（`cache`）
+//! - 限流（`rate_limit`）
+//! - 上游地址、权重与选择策略（`upstream.bases`、`upstream.weights`、`upstream.policy`）
+//! - 上游主动健康检查的间隔（`upstream.health_check_secs`）
+//! - 日志过滤规则（`log.level`）
+//! - CORS允许的来源（`cors.allowed_origins`）
+//!
+//! 其余配置项（如监听地址、端口与路由前缀）需要重启服务才能生效，修改时保持原值并记录警告。
+//!
+//! 替换后，读取[`LiveConfig`]的响应缓存与CORS立即生效；客户端的上游、健康检查与限流，以及日志过滤规则
+//! 需要由调用方根据[`Reload::applied`]更新。
+//!
+//! ```rust
+//! use easy_msr_api::config::{Config, reload::LiveConfig, settings::Settings};
+//!
+//! let live = LiveConfig::new(Config::from_settings(Settings::default()).unwrap());
+//! let settings = Settings::from_toml("[server]\nport = 9000\n[rate_limit]\nrequests_per_second = 5.0\n").unwrap();
+//! let reload = live.reload(Config::from_settings(settings).unwrap());
+//! assert_eq!(reload.applied, ["rate_limit"]);
+//! assert_eq!(reload.rejected, ["server.port"]);
+//! assert_eq!(live.load().server_port, 8080);
+//! ```
+
+use crate::config::Config;
+use arc_swap::ArcSwap;
+use std::sync::Arc;
+
+/// 当前生效的配置
+///
+/// 克隆后的实例共享同一份配置。
+#[derive(Clone)]
+pub struct LiveConfig {
+    current: Arc<ArcSwap<Config>>,
+}
+
+/// 一次重新加载的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reload {
+    /// 已生效的配置项
+    pub applied: Vec<&'static str>,
+
+    /// 需要重启服务才能生效、本次被忽略的配置项
+    pub rejected: Vec<&'static str>,
+}
+
+impl Reload {
+    /// 配置项`key`是否已生效
+    pub fn is_applied(&self, key: &str) -> bool {
+        self.applied.contains(&key)
+    }
+}
+
+impl std::fmt::Debug for LiveConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("LiveConfig").field(&self.current.load()).finish()
+    }
+}
+
+impl LiveConfig {
+    /// 使用初始配置创建
+    pub fn new(config: Config) -> Self {
+        Self {
+            current: Arc::new(ArcSwap::from_pointee(config)),
+        }
+    }
+
+    /// 当前生效的配置
+    pub fn load(&self) -> Arc<Config> {
+        self.current.load_full()
+    }
+
+    /// 用`config`中可以在运行中生效的配置项替换当前配置
+    ///
+    /// 需要重启才能生效的配置项保持原值，并记录警告。
+    pub fn reload(&self, config: Config) -> Reload {
+        let current = self.load();
+        let mut next = (*current).clone();
+        let mut reload = Reload::default();
+
+        if current.cache != config.cache {
+            next.cache = config.cache;
+            reload.applied.push("cache");
+        }
+        if current.rate_limit != config.rate_limit {
+            next.rate_limit = config.rate_limit;
+            reload.applied.push("rate_limit");
+        }
+        if current.upstreams != config.upstreams || current.upstream_policy != config.upstream_policy {
+            next.remote_base = config.remote_base;
+            next.upstreams = config.upstreams;
+            next.upstream_policy = config.upstream_policy;
+            reload.applied.push("upstream.bases");
+        }
+        if current.health_check_interval != config.health_check_interval {
+            next.health_check_interval = config.health_check_interval;
+            reload.applied.push("upstream.health_check_secs");
+        }
+        if current.log_filter != config.log_filter {
+            next.log_filter = config.log_filter;
+            reload.applied.push("log.level");
+        }
+        match (&current.cors, config.cors) {
+            (Some(cors), Some(new)) => {
+                if cors.allowed_origins != new.allowed_origins {
+                    reload.applied.push("cors.allowed_origins");
+                }
+                if cors.allowed_methods != new.allowed_methods {
+                    reload.rejected.push("cors.allowed_methods");
+                }
+                if cors.max_age != new.max_age {
+                    reload.rejected.push("cors.max_age_secs");
+                }
+                if let Some(cors) = &mut next.cors {
+                    cors.allowed_origins = new.allowed_origins;
+                }
+            }
+            (None, None) => {}
+            _ => reload.rejected.push("cors"),
+        }
+
+        let restart_required = [
+            ("server.bind", current.bind != config.bind),
+            ("server.port", current.server_port != config.server_port),
+            ("server.path_prefix", current.path_prefix != config.path_prefix),
+            ("server.public_url", current.public_url != config.public_url),
+            ("server.passthrough", current.passthrough != config.passthrough),
+            ("server.shutdown_timeout_secs", current.shutdown_timeout != config.shutdown_timeout),
+            ("upstream.timeout_secs", current.upstream_timeout != config.upstream_timeout),
+            ("compression", current.compression != config.compression),
+            ("security_headers", current.security_headers != config.security_headers),
+            ("auth", current.auth != config.auth),
+            ("breaker", current.breaker != config.breaker),
+            ("retry", current.retry != config.retry),
+            ("log.format", current.log_format != config.log_format),
+            ("otel", current.telemetry != config.telemetry),
+        ];
+        reload
+            .rejected
+            .extend(restart_required.into_iter().filter(|(_, changed)| *changed).map(|(key, _)| key));
+
+        for key in &reload.rejected {
+            tracing::warn!("配置项{}需要重启服务才能生效，已忽略其修改", key);
+        }
+        if !reload.applied.is_empty() {
+            tracing::info!("配置已重新加载，生效的配置项: {}", reload.applied.join(", "));
+            self.current.store(Arc::new(next));
+        }
+        reload
+    }
+}
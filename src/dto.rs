@@ -5,8 +5,13 @@
 //! 这些结构体用于序列化和反序列化JSON数据，并提供了OpenAPI文档支持。
 //! 所有结构体都实现了`Serialize`、`Deserialize`和`ToSchema` trait。
 
+use crate::client::breaker::{CircuitState, CircuitStatus};
+use crate::client::coalesce::CoalesceStats;
+use crate::client::upstream::UpstreamStatus;
+use crate::config::ConfigSummary;
 use serde::{Deserialize, Serialize};
-use utoipa::{IntoParams, ToSchema, schema};
+use std::collections::BTreeMap;
+use utoipa::{IntoParams, ToSchema};
 
 /// 统一的API响应格式
 ///
@@ -42,7 +47,7 @@ impl<T> ApiResp<T> {
     /// # 示例
     ///
     /// ```rust
-    /// use easy_msr_api::web::dto::ApiResp;
+    /// use easy_msr_api::dto::ApiResp;
     ///
     /// let resp = ApiResp::success("Hello, World!");
     /// assert_eq!(resp.code, 0);
@@ -64,7 +69,7 @@ impl<T> ApiResp<T> {
     /// # 示例
     ///
     /// ```rust
-    /// use easy_msr_api::web::dto::ApiResp;
+    /// use easy_msr_api::dto::ApiResp;
     ///
     /// let resp: ApiResp<String> = ApiResp::error("参数错误".to_string());
     /// assert_eq!(resp.code, -1);
@@ -261,6 +266,96 @@ pub struct AlbumDetailData {
 /// 专辑详情响应类型
 pub type AlbumDetailResp = ApiResp<AlbumDetailData>;
 
+/// 完整专辑数据中的曲目
+///
+/// 拉取成功时包含完整的歌曲详情，失败时包含错误描述。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, ToSchema)]
+pub struct AlbumFullTrack {
+    /// 歌曲唯一标识符（cid）
+    #[serde(rename = "cid")]
+    #[schema(value_type = String, example = "953953")]
+    pub id: String,
+
+    /// 歌曲详情，拉取失败时为空
+    pub song: Option<SongData>,
+
+    /// 拉取失败时对外展示的错误信息
+    pub error: Option<String>,
+}
+
+/// 完整专辑数据
+///
+/// 包含专辑信息以及每首歌曲的完整详情（音频、歌词URL等）。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, ToSchema)]
+pub struct AlbumFullData {
+    /// 专辑唯一标识符（cid）
+    #[serde(rename = "cid")]
+    #[schema(value_type = String, example = "3888")]
+    pub id: String,
+
+    /// 专辑名称
+    #[schema(value_type = String, example = "Little Wish")]
+    pub name: String,
+
+    /// 专辑简介
+    pub intro: String,
+
+    /// 所属分类
+    #[schema(value_type = String, example = "arknights")]
+    pub belong: String,
+
+    /// 封面图片URL
+    #[serde(rename = "coverUrl")]
+    pub cover_url: String,
+
+    /// 详情页封面URL
+    #[serde(rename = "coverDeUrl")]
+    pub cover_de_url: String,
+
+    /// 专辑中的曲目，顺序与专辑详情一致
+    pub songs: Vec<AlbumFullTrack>,
+
+    /// 拉取失败的曲目数量
+    pub failed: usize,
+}
+
+/// 完整专辑响应类型
+pub type AlbumFullResp = ApiResp<AlbumFullData>;
+
+/// 批量查询中单个cid的结果
+///
+/// 查询成功时`data`有值，失败时`error`包含对外展示的错误信息。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, ToSchema)]
+pub struct BatchEntry<T> {
+    /// 查询到的数据，失败时为空
+    pub data: Option<T>,
+
+    /// 查询失败时对外展示的错误信息
+    pub error: Option<String>,
+}
+
+impl<T> BatchEntry<T> {
+    /// 由单次查询的结果创建
+    pub fn from_result<E: std::fmt::Display>(result: Result<T, E>) -> Self {
+        match result {
+            Ok(data) => Self {
+                data: Some(data),
+                error: None,
+            },
+            Err(e) => Self {
+                data: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+/// 批量获取歌曲的响应类型，键为歌曲cid
+pub type SongBatchResp = ApiResp<BTreeMap<String, BatchEntry<SongData>>>;
+
+/// 批量获取专辑的响应类型，键为专辑cid
+pub type AlbumBatchResp = ApiResp<BTreeMap<String, BatchEntry<AlbumData>>>;
+
 /// 所有专辑列表单项
 ///
 /// 简化版的专辑信息，用于列表展示。
@@ -386,6 +481,23 @@ pub struct AllNewsQuery {
     pub last_cid: Option<String>,
 }
 
+/// 新闻订阅源查询参数
+#[derive(Serialize, Deserialize, Debug, IntoParams)]
+pub struct NewsFeedQuery {
+    /// 拉取的新闻页数，默认为1
+    pub pages: Option<usize>,
+
+    /// 是否包含新闻正文，默认为false
+    pub content: Option<bool>,
+}
+
+/// 播放列表查询参数
+#[derive(Serialize, Deserialize, Debug, IntoParams)]
+pub struct PlaylistQuery {
+    /// 是否使用代理服务地址作为曲目地址，默认为false（使用上游音频URL）
+    pub proxy: Option<bool>,
+}
+
 /// 搜索新闻响应类型
 pub type SearchNewsResp = ApiResp<NewsData>;
 
@@ -411,6 +523,121 @@ pub struct SearchData {
 /// 综合搜索响应类型
 pub type SearchResp = ApiResp<SearchData>;
 
+/// 歌曲搜索查询参数
+///
+/// 至少需要提供一个参数，多个参数同时提供时结果需全部满足。
+#[derive(Serialize, Deserialize, Debug, Clone, Default, IntoParams)]
+pub struct SongSearchQuery {
+    /// 关键词，匹配歌曲名、艺术家或专辑名
+    pub keyword: Option<String>,
+
+    /// 艺术家名称
+    pub artist: Option<String>,
+
+    /// 专辑名称或专辑cid
+    pub album: Option<String>,
+}
+
+/// 歌曲搜索结果项
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, ToSchema)]
+pub struct SongSearchItem {
+    /// 歌曲唯一标识符（cid）
+    #[serde(rename = "cid")]
+    #[schema(value_type = String, example = "953953")]
+    pub id: String,
+
+    /// 歌曲名称
+    #[schema(value_type = String, example = "Little Wish")]
+    pub name: String,
+
+    /// 所属专辑cid
+    #[serde(rename = "albumCid")]
+    #[schema(value_type = String, example = "3888")]
+    pub album_id: String,
+
+    /// 所属专辑名称
+    #[serde(rename = "albumName")]
+    #[schema(value_type = String, example = "Little Wish")]
+    pub album_name: String,
+
+    /// 艺术家列表
+    pub artists: Vec<String>,
+}
+
+/// 歌曲搜索结果数据
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, ToSchema)]
+pub struct SongSearchData {
+    /// 歌曲列表
+    pub list: Vec<SongSearchItem>,
+}
+
+/// 歌曲搜索响应类型
+pub type SongSearchResp = ApiResp<SongSearchData>;
+
+/// 包含歌曲的综合搜索结果数据
+///
+/// 在上游综合搜索结果的基础上增加了本地歌曲搜索结果。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, ToSchema)]
+pub struct ExtendedSearchData {
+    /// 专辑搜索结果
+    pub albums: SearchAlbumData,
+
+    /// 新闻搜索结果
+    pub news: NewsData,
+
+    /// 歌曲搜索结果
+    pub songs: SongSearchData,
+}
+
+/// 包含歌曲的综合搜索响应类型
+pub type ExtendedSearchResp = ApiResp<ExtendedSearchData>;
+
+/// 艺术家概要
+///
+/// 艺术家列表中的单项，名称已经过规范化合并。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, ToSchema)]
+pub struct ArtistSummary {
+    /// 艺术家名称（出现次数最多的写法）
+    #[schema(value_type = String, example = "塞壬唱片-MSR")]
+    pub name: String,
+
+    /// 合并到该艺术家下的所有写法
+    pub variants: Vec<String>,
+
+    /// 参与的专辑数量
+    #[serde(rename = "albumCount")]
+    pub album_count: usize,
+
+    /// 参与的歌曲数量
+    #[serde(rename = "songCount")]
+    pub song_count: usize,
+}
+
+/// 艺术家列表响应类型
+pub type ArtistListResp = ApiResp<Vec<ArtistSummary>>;
+
+/// 艺术家详情
+///
+/// 包含艺术家参与的所有专辑和歌曲。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, ToSchema)]
+pub struct ArtistDetail {
+    /// 艺术家名称（出现次数最多的写法）
+    #[schema(value_type = String, example = "塞壬唱片-MSR")]
+    pub name: String,
+
+    /// 合并到该艺术家下的所有写法
+    pub variants: Vec<String>,
+
+    /// 参与的专辑
+    pub albums: Vec<AllAlbumsItem>,
+
+    /// 参与的歌曲
+    pub songs: Vec<AllSongsItem>,
+}
+
+/// 艺术家详情响应类型
+pub type ArtistResp = ApiResp<ArtistDetail>;
+
 /// 新闻详情数据
 ///
 /// 包含新闻的完整内容。
@@ -473,3 +700,152 @@ pub struct FontData {
 
 /// 字体响应类型
 pub type FontResp = ApiResp<FontData>;
+
+/// 服务健康状态
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct HealthData {
+    /// 整体状态：`ok`表示正常，`degraded`表示上游熔断中或存在不健康的上游
+    #[schema(example = "ok")]
+    pub status: String,
+
+    /// 各上游的状态
+    pub upstreams: Vec<UpstreamStatus>,
+
+    /// 上游熔断器状态，未启用熔断时为空
+    pub circuit: Option<CircuitStatus>,
+
+    /// 请求合并统计
+    pub coalescing: CoalesceStats,
+}
+
+impl HealthData {
+    /// 由上游状态、熔断器状态与请求合并统计生成健康状态
+    pub fn new(
+        upstreams: Vec<UpstreamStatus>,
+        circuit: Option<CircuitStatus>,
+        coalescing: CoalesceStats,
+    ) -> Self {
+        let degraded = circuit
+            .as_ref()
+            .is_some_and(|c| c.state != CircuitState::Closed)
+            || upstreams.iter().any(|u| !u.healthy);
+        Self {
+            status: if degraded { "degraded" } else { "ok" }.to_string(),
+            upstreams,
+            circuit,
+            coalescing,
+        }
+    }
+}
+
+/// 服务健康状态响应类型
+pub type HealthResp = ApiResp<HealthData>;
+
+/// 存活检查结果
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct LivenessData {
+    /// 固定为`ok`
+    #[schema(example = "ok")]
+    pub status: String,
+}
+
+/// 存活检查响应类型
+pub type LivenessResp = ApiResp<LivenessData>;
+
+/// 就绪检查结果
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct ReadinessData {
+    /// 是否就绪：至少一个上游可以访问，且熔断器未打开
+    pub ready: bool,
+
+    /// 各上游的状态
+    pub upstreams: Vec<UpstreamStatus>,
+
+    /// 上游熔断器状态，未启用熔断时为空
+    pub circuit: Option<CircuitStatus>,
+
+    /// 检查时间（Unix时间戳，秒）
+    #[serde(rename = "checkedAt")]
+    pub checked_at: i64,
+}
+
+impl ReadinessData {
+    /// 由主动检查后的上游状态与熔断器状态生成就绪检查结果
+    pub fn new(upstreams: Vec<UpstreamStatus>, circuit: Option<CircuitStatus>) -> Self {
+        let ready = upstreams.iter().any(|u| u.healthy)
+            && circuit.as_ref().is_none_or(|c| c.state != CircuitState::Open);
+        Self {
+            ready,
+            upstreams,
+            circuit,
+            checked_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// 就绪检查响应类型
+pub type ReadinessResp = ApiResp<ReadinessData>;
+
+/// 版本信息
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct VersionData {
+    /// crate版本号
+    #[schema(example = "0.1.3")]
+    pub version: String,
+
+    /// 构建时的git提交哈希，未启用`web` feature或无法获取时为空
+    #[serde(rename = "gitHash")]
+    pub git_hash: Option<String>,
+
+    /// 不含敏感信息的配置摘要，未提供时为空
+    pub config: Option<ConfigSummary>,
+}
+
+impl VersionData {
+    /// 当前构建的版本信息
+    pub fn new(config: Option<ConfigSummary>) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: option_env!("EASY_MSR_GIT_HASH").map(str::to_string),
+            config,
+        }
+    }
+}
+
+/// 版本信息响应类型
+pub type VersionResp = ApiResp<VersionData>;
+
+/// API密钥的用量
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct UsageData {
+    /// 密钥名称
+    #[schema(example = "partner-a")]
+    pub name: String,
+
+    /// 服务启动以来放行的请求数
+    #[serde(rename = "totalRequests")]
+    pub total_requests: u64,
+
+    /// 当日（UTC）放行的请求数
+    #[serde(rename = "todayRequests")]
+    pub today_requests: u64,
+
+    /// 超过速率限制或每日配额被拒绝的请求数
+    #[serde(rename = "rejectedRequests")]
+    pub rejected_requests: u64,
+
+    /// 每秒请求数上限，不限制时为空
+    #[serde(rename = "requestsPerSecond")]
+    pub requests_per_second: Option<f64>,
+
+    /// 每日配额，不限制时为空
+    #[serde(rename = "dailyQuota")]
+    pub daily_quota: Option<u64>,
+
+    /// 当日剩余的配额，不限制时为空
+    #[serde(rename = "remainingToday")]
+    pub remaining_today: Option<u64>,
+}
+
+/// API密钥用量响应类型
+pub type UsageResp = ApiResp<UsageData>;
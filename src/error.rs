@@ -6,10 +6,13 @@
 //! 包括网络请求错误、配置错误、资源未找到等。
 
 use axum::{
-    http::StatusCode,
+    http::{HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
+use tower::BoxError;
 
 /// 应用程序错误类型
 /// 
@@ -46,6 +49,125 @@ pub enum AppError {
     /// 当环境变量或配置文件格式不正确时返回
     #[error("配置错误: {0}")]
     Config(String),
+
+    /// 远程API响应解析错误
+    /// 
+    /// 当远程API返回的数据不符合预期格式时返回
+    #[error("解析远程API响应失败: {0}")]
+    Decode(#[from] serde_json::Error),
+
+    /// 合并请求的共享错误
+    /// 
+    /// 多个相同的并发请求被合并时，等待方收到的是同一个错误的共享引用
+    #[error("{0}")]
+    Coalesced(Arc<AppError>),
+
+    /// 上游熔断中
+    /// 
+    /// 上游连续失败触发熔断后，在冷却期内直接拒绝请求，`retry_after`为建议的重试等待时间
+    #[error("远程服务熔断中，{}秒后重试", retry_after.as_secs_f64().ceil())]
+    CircuitOpen { retry_after: Duration },
+
+    /// 上游限流
+    /// 
+    /// 上游返回`429 Too Many Requests`且重试后仍被限流时返回，`retry_after`为上游建议的等待时间
+    #[error("远程服务限流，{}秒后重试", retry_after.as_secs_f64().ceil())]
+    RateLimited { retry_after: Duration },
+
+    /// 出站请求中间件错误
+    /// 
+    /// 当出站请求的`tower`中间件返回的错误既不是`reqwest::Error`也不是`AppError`时返回
+    #[error("出站请求中间件错误: {0}")]
+    Middleware(BoxError),
+
+    /// 未认证
+    /// 
+    /// 启用API密钥认证后，请求中没有API密钥或密钥无效时返回
+    #[error("缺少或无效的API密钥")]
+    Unauthorized,
+
+    /// 超出API密钥的请求配额
+    /// 
+    /// API密钥超过每秒请求数或每日配额时返回，`retry_after`为建议的重试等待时间
+    #[error("超出API密钥的请求配额，{}秒后重试", retry_after.as_secs_f64().ceil())]
+    QuotaExceeded { retry_after: Duration },
+}
+
+impl From<BoxError> for AppError {
+    /// 还原中间件栈中的原始错误，无法还原时包装为[`AppError::Middleware`]
+    fn from(error: BoxError) -> Self {
+        let error = match error.downcast::<reqwest::Error>() {
+            Ok(e) => return AppError::Remote(*e),
+            Err(error) => error,
+        };
+        match error.downcast::<AppError>() {
+            Ok(e) => *e,
+            Err(error) => AppError::Middleware(error),
+        }
+    }
+}
+
+impl AppError {
+    /// 错误对应的HTTP状态码和对外展示的错误信息
+    fn status_and_message(&self) -> (StatusCode, &str) {
+        match self {
+            AppError::Remote(e) if e.is_timeout() => (StatusCode::REQUEST_TIMEOUT, "请求超时"),
+            AppError::Remote(_) => (StatusCode::BAD_GATEWAY, "远程服务暂时不可用"),
+            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
+            AppError::NotFound => (StatusCode::NOT_FOUND, "请求的资源不存在"),
+            AppError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误"),
+            AppError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, "配置错误"),
+            AppError::Decode(_) => (StatusCode::BAD_GATEWAY, "远程服务暂时不可用"),
+            AppError::Coalesced(inner) => inner.status_and_message(),
+            AppError::CircuitOpen { .. } => (StatusCode::SERVICE_UNAVAILABLE, "远程服务熔断中，请稍后重试"),
+            AppError::RateLimited { .. } => (StatusCode::TOO_MANY_REQUESTS, "远程服务限流，请稍后重试"),
+            AppError::Middleware(_) => (StatusCode::BAD_GATEWAY, "远程服务暂时不可用"),
+            AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "缺少或无效的API密钥"),
+            AppError::QuotaExceeded { .. } => (StatusCode::TOO_MANY_REQUESTS, "超出API密钥的请求配额，请稍后重试"),
+        }
+    }
+
+    /// 对外展示的错误信息，与错误响应中的`error`字段相同，不包含上游地址等内部细节
+    /// 
+    /// # 示例
+    /// 
+    /// ```rust
+    /// use easy_msr_api::error::AppError;
+    /// 
+    /// let error = AppError::Internal("读取/etc/msr.toml失败".into());
+    /// assert_eq!(error.client_message(), "服务器内部错误");
+    /// ```
+    pub fn client_message(&self) -> &str {
+        self.status_and_message().1
+    }
+
+    /// 建议客户端重试前等待的时间
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            AppError::CircuitOpen { retry_after }
+            | AppError::RateLimited { retry_after }
+            | AppError::QuotaExceeded { retry_after } => {
+                Some(*retry_after)
+            }
+            AppError::Coalesced(inner) => inner.retry_after(),
+            _ => None,
+        }
+    }
+
+    /// 是否为上游故障（连接错误、超时、5xx响应或出站请求中间件错误）
+    /// 
+    /// 4xx响应和响应解析错误不视为上游故障。
+    pub fn is_upstream_failure(&self) -> bool {
+        match self {
+            AppError::Remote(e) => match e.status() {
+                Some(status) => status.is_server_error(),
+                None => !e.is_decode() && !e.is_builder(),
+            },
+            AppError::Coalesced(inner) => inner.is_upstream_failure(),
+            AppError::Middleware(_) => true,
+            _ => false,
+        }
+    }
 }
 
 impl IntoResponse for AppError {
@@ -58,21 +180,37 @@ impl IntoResponse for AppError {
     /// - 资源未找到 -> 404 Not Found
     /// - 内部错误 -> 500 Internal Server Error
     /// - 配置错误 -> 500 Internal Server Error
+    /// - 响应解析错误 -> 502 Bad Gateway
+    /// - 合并请求的共享错误 -> 与原始错误相同
+    /// - 上游熔断中 -> 503 Service Unavailable，附带`Retry-After`响应头
+    /// - 上游限流 -> 429 Too Many Requests，附带`Retry-After`响应头
+    /// - 出站请求中间件错误 -> 502 Bad Gateway
+    /// - 未认证 -> 401 Unauthorized
+    /// - 超出API密钥的请求配额 -> 429 Too Many Requests，附带`Retry-After`响应头
+    /// 
+    /// 响应体为`{"error": 错误信息, "code": 状态码}`，在Web路由中处理的请求还会带有`requestId`。
     fn into_response(self) -> Response {
-        let (status, message) = match self {
-            AppError::Remote(ref e) if e.is_timeout() => (StatusCode::REQUEST_TIMEOUT, "请求超时"),
-            AppError::Remote(_) => (StatusCode::BAD_GATEWAY, "远程服务暂时不可用"),
-            AppError::BadRequest(ref msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
-            AppError::NotFound => (StatusCode::NOT_FOUND, "请求的资源不存在"),
-            AppError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误"),
-            AppError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, "配置错误"),
-        };
+        let (status, message) = self.status_and_message();
         
         let body = serde_json::json!({
             "error": message,
             "code": status.as_u16()
         });
+        #[cfg(feature = "web")]
+        let body = match crate::web::middleware::current_request_id() {
+            Some(request_id) => {
+                let mut body = body;
+                body["requestId"] = request_id.into();
+                body
+            }
+            None => body,
+        };
         
-        (status, axum::Json(body)).into_response()
+        let mut response = (status, axum::Json(body)).into_response();
+        if let Some(retry_after) = self.retry_after() {
+            let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
     }
 }
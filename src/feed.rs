@@ -0,0 +1,96 @@
+//! # 订阅源生成模块
+//!
+//! 将MSR API返回的数据转换为标准的XML订阅源，便于在阅读器中订阅。
+//!
+//! ## 模块结构
+//!
+//! - [`news`] - 新闻（动向）订阅源，支持RSS 2.0与Atom格式
+//! - [`podcast`] - 歌曲播客订阅源，每首歌曲作为一期带音频附件的节目
+
+use crate::client::remote::RemoteApiClient;
+use crate::dto::SongData;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone};
+use futures::{StreamExt, stream};
+
+pub mod news;
+pub mod podcast;
+
+/// MSR官网地址，用于生成条目链接
+pub const SITE_URL: &str = "https://monster-siren.hypergryph.com";
+
+/// 批量拉取详情时的最大并发数
+pub(crate) const FETCH_CONCURRENCY: usize = 4;
+
+/// MSR数据使用的时区（UTC+8）
+const MSR_OFFSET_SECS: i32 = 8 * 3600;
+
+/// 转义XML文本与属性值中的特殊字符
+///
+/// # 示例
+///
+/// ```rust
+/// use easy_msr_api::feed::escape_xml;
+///
+/// assert_eq!(escape_xml("<a href=\"x\">&</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+/// ```
+pub fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // XML 1.0 不允许的控制字符直接丢弃
+            c if (c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// 解析MSR接口中的日期字符串
+///
+/// 支持`2022-01-01`与`2022-01-01 12:00:00`两种格式，统一按UTC+8解释。
+/// 无法解析时返回`None`。
+///
+/// # 示例
+///
+/// ```rust
+/// use easy_msr_api::feed::parse_msr_date;
+///
+/// let date = parse_msr_date("2022-01-01").unwrap();
+/// assert_eq!(date.to_rfc3339(), "2022-01-01T00:00:00+08:00");
+/// assert!(parse_msr_date("not a date").is_none());
+/// ```
+pub fn parse_msr_date(date: &str) -> Option<DateTime<FixedOffset>> {
+    let date = date.trim();
+    let naive = NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })?;
+    FixedOffset::east_opt(MSR_OFFSET_SECS)?
+        .from_local_datetime(&naive)
+        .single()
+}
+
+/// 并发拉取歌曲详情，拉取失败的歌曲会被跳过并记录警告
+pub(crate) async fn fetch_songs(client: &RemoteApiClient, ids: Vec<String>) -> Vec<SongData> {
+    stream::iter(ids)
+        .map(|id| async move {
+            client
+                .get_song(id.clone())
+                .await
+                .map(|resp| resp.data)
+                .inspect_err(|e| tracing::warn!("拉取歌曲详情失败 cid={}: {}", id, e))
+                .ok()
+        })
+        .buffered(FETCH_CONCURRENCY)
+        .filter_map(|song| async move { song })
+        .collect()
+        .await
+}
@@ -0,0 +1,287 @@
+//! # 新闻订阅源
+//!
+//! 将`get_all_news`的分页结果（可选附带`get_news_detail`的正文）转换为
+//! RSS 2.0与Atom格式的XML文档。
+//!
+//! 每个条目使用新闻cid生成稳定的GUID，使用`date`作为发布时间，
+//! 使用`cate`作为分类。
+
+use crate::client::remote::RemoteApiClient;
+use crate::dto::NewsItem;
+use crate::error::AppError;
+use crate::feed::{FETCH_CONCURRENCY, SITE_URL, escape_xml, parse_msr_date};
+use chrono::{DateTime, FixedOffset};
+use futures::{StreamExt, stream};
+use std::fmt::Write;
+
+/// 新闻订阅源配置
+#[derive(Clone, Debug)]
+pub struct NewsFeedOptions {
+    /// 订阅源标题
+    pub title: String,
+
+    /// 订阅源描述
+    pub description: String,
+
+    /// 订阅源对应的网页地址
+    pub link: String,
+
+    /// 订阅源自身的访问地址，用于`rel="self"`链接
+    pub self_link: Option<String>,
+
+    /// 拉取的新闻页数，每页由上游决定条目数量
+    pub pages: usize,
+
+    /// 是否为每条新闻拉取正文
+    pub include_content: bool,
+}
+
+impl Default for NewsFeedOptions {
+    fn default() -> Self {
+        Self {
+            title: "塞壬唱片-MSR 动向".into(),
+            description: "塞壬唱片官网新闻动向".into(),
+            link: format!("{}/info", SITE_URL),
+            self_link: None,
+            pages: 1,
+            include_content: false,
+        }
+    }
+}
+
+/// 订阅源中的单条新闻
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NewsEntry {
+    /// 新闻列表中的基本信息
+    pub item: NewsItem,
+
+    /// 作者，仅在拉取了新闻详情时存在
+    pub author: Option<String>,
+
+    /// 新闻正文（HTML），仅在拉取了新闻详情时存在
+    pub content: Option<String>,
+}
+
+impl From<NewsItem> for NewsEntry {
+    fn from(item: NewsItem) -> Self {
+        Self {
+            item,
+            author: None,
+            content: None,
+        }
+    }
+}
+
+impl NewsEntry {
+    /// 条目在MSR官网上的链接
+    pub fn link(&self) -> String {
+        format!("{}/info/{}", SITE_URL, self.item.id)
+    }
+
+    /// 基于新闻cid生成的稳定GUID
+    pub fn guid(&self) -> String {
+        format!("msr-news-{}", self.item.id)
+    }
+
+    /// 条目的发布时间，日期无法解析时返回`None`
+    pub fn published(&self) -> Option<DateTime<FixedOffset>> {
+        parse_msr_date(&self.item.date)
+    }
+}
+
+/// 从远程API拉取用于生成订阅源的新闻条目
+///
+/// 按`options.pages`逐页调用`get_all_news`，在上游返回`end`时提前结束。
+/// 若启用了`include_content`，会并发拉取每条新闻的详情；
+/// 单条详情拉取失败时仅记录警告，该条目不包含正文。
+///
+/// # 参数
+///
+/// * `client` - 远程API客户端
+/// * `options` - 订阅源配置
+///
+/// # 返回
+///
+/// 按上游顺序排列的新闻条目
+pub async fn fetch_entries(
+    client: &RemoteApiClient,
+    options: &NewsFeedOptions,
+) -> Result<Vec<NewsEntry>, AppError> {
+    let mut items: Vec<NewsItem> = Vec::new();
+    let mut last_cid = None;
+
+    for _ in 0..options.pages.max(1) {
+        let page = client.get_all_news(last_cid.take()).await?.data;
+        let end = page.end;
+        items.extend(page.list);
+        match items.last() {
+            Some(last) if !end => last_cid = Some(last.id.clone()),
+            _ => break,
+        }
+    }
+
+    if !options.include_content {
+        return Ok(items.into_iter().map(NewsEntry::from).collect());
+    }
+
+    let entries = stream::iter(items)
+        .map(|item| async move {
+            match client.get_news_detail(item.id.clone()).await {
+                Ok(detail) => NewsEntry {
+                    item,
+                    author: Some(detail.data.author),
+                    content: Some(detail.data.content),
+                },
+                Err(e) => {
+                    tracing::warn!("拉取新闻详情失败 cid={}: {}", item.id, e);
+                    NewsEntry::from(item)
+                }
+            }
+        })
+        .buffered(FETCH_CONCURRENCY)
+        .collect()
+        .await;
+
+    Ok(entries)
+}
+
+/// 所有条目中最新的发布时间
+fn latest(entries: &[NewsEntry]) -> Option<DateTime<FixedOffset>> {
+    entries.iter().filter_map(NewsEntry::published).max()
+}
+
+/// 生成RSS 2.0格式的订阅源
+///
+/// # 示例
+///
+/// ```rust
+/// use easy_msr_api::dto::NewsItem;
+/// use easy_msr_api::feed::news::{NewsEntry, NewsFeedOptions, render_rss};
+///
+/// let entry = NewsEntry::from(NewsItem {
+///     id: "9140".into(),
+///     title: "新专辑上线".into(),
+///     cate: 8,
+///     date: "2024-05-20".into(),
+/// });
+/// let xml = render_rss(&[entry], &NewsFeedOptions::default());
+/// assert!(xml.contains(r#"<guid isPermaLink="false">msr-news-9140</guid>"#));
+/// assert!(xml.contains("<pubDate>Mon, 20 May 2024 00:00:00 +0800</pubDate>"));
+/// assert!(xml.contains("<category>8</category>"));
+/// ```
+pub fn render_rss(entries: &[NewsEntry], options: &NewsFeedOptions) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(concat!(
+        "<rss version=\"2.0\"",
+        " xmlns:atom=\"http://www.w3.org/2005/Atom\"",
+        " xmlns:content=\"http://purl.org/rss/1.0/modules/content/\"",
+        " xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n"
+    ));
+    xml.push_str("<channel>\n");
+    let _ = writeln!(xml, "<title>{}</title>", escape_xml(&options.title));
+    let _ = writeln!(xml, "<link>{}</link>", escape_xml(&options.link));
+    let _ = writeln!(xml, "<description>{}</description>", escape_xml(&options.description));
+    xml.push_str("<language>zh-cn</language>\n");
+    if let Some(self_link) = &options.self_link {
+        let _ = writeln!(
+            xml,
+            "<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>",
+            escape_xml(self_link)
+        );
+    }
+    if let Some(updated) = latest(entries) {
+        let _ = writeln!(xml, "<lastBuildDate>{}</lastBuildDate>", updated.to_rfc2822());
+    }
+
+    for entry in entries {
+        xml.push_str("<item>\n");
+        let _ = writeln!(xml, "<title>{}</title>", escape_xml(&entry.item.title));
+        let _ = writeln!(xml, "<link>{}</link>", escape_xml(&entry.link()));
+        let _ = writeln!(xml, "<guid isPermaLink=\"false\">{}</guid>", escape_xml(&entry.guid()));
+        if let Some(published) = entry.published() {
+            let _ = writeln!(xml, "<pubDate>{}</pubDate>", published.to_rfc2822());
+        }
+        let _ = writeln!(xml, "<category>{}</category>", entry.item.cate);
+        if let Some(author) = &entry.author {
+            let _ = writeln!(xml, "<dc:creator>{}</dc:creator>", escape_xml(author));
+        }
+        if let Some(content) = &entry.content {
+            let _ = writeln!(xml, "<content:encoded>{}</content:encoded>", escape_xml(content));
+        }
+        xml.push_str("</item>\n");
+    }
+
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+/// 生成Atom格式的订阅源
+///
+/// # 示例
+///
+/// ```rust
+/// use easy_msr_api::dto::NewsItem;
+/// use easy_msr_api::feed::news::{NewsEntry, NewsFeedOptions, render_atom};
+///
+/// let entry = NewsEntry::from(NewsItem {
+///     id: "9140".into(),
+///     title: "新专辑上线".into(),
+///     cate: 8,
+///     date: "2024-05-20".into(),
+/// });
+/// let xml = render_atom(&[entry], &NewsFeedOptions::default());
+/// assert!(xml.contains("<id>tag:monster-siren.hypergryph.com,2020:news/9140</id>"));
+/// assert!(xml.contains("<updated>2024-05-20T00:00:00+08:00</updated>"));
+/// assert!(xml.contains(r#"<category term="8"/>"#));
+/// ```
+pub fn render_atom(entries: &[NewsEntry], options: &NewsFeedOptions) -> String {
+    let updated = latest(entries)
+        .unwrap_or_else(|| chrono::Utc::now().fixed_offset())
+        .to_rfc3339();
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"zh-cn\">\n");
+    let _ = writeln!(xml, "<id>{}</id>", escape_xml(&options.link));
+    let _ = writeln!(xml, "<title>{}</title>", escape_xml(&options.title));
+    let _ = writeln!(xml, "<subtitle>{}</subtitle>", escape_xml(&options.description));
+    let _ = writeln!(xml, "<updated>{}</updated>", updated);
+    let _ = writeln!(xml, "<link href=\"{}\"/>", escape_xml(&options.link));
+    if let Some(self_link) = &options.self_link {
+        let _ = writeln!(xml, "<link rel=\"self\" href=\"{}\"/>", escape_xml(self_link));
+    }
+    xml.push_str("<author><name>塞壬唱片-MSR</name></author>\n");
+
+    for entry in entries {
+        xml.push_str("<entry>\n");
+        let _ = writeln!(
+            xml,
+            "<id>tag:monster-siren.hypergryph.com,2020:news/{}</id>",
+            escape_xml(&entry.item.id)
+        );
+        let _ = writeln!(xml, "<title>{}</title>", escape_xml(&entry.item.title));
+        let _ = writeln!(xml, "<link href=\"{}\"/>", escape_xml(&entry.link()));
+        match entry.published() {
+            Some(published) => {
+                let published = published.to_rfc3339();
+                let _ = writeln!(xml, "<published>{}</published>", published);
+                let _ = writeln!(xml, "<updated>{}</updated>", published);
+            }
+            None => {
+                let _ = writeln!(xml, "<updated>{}</updated>", updated);
+            }
+        }
+        let _ = writeln!(xml, "<category term=\"{}\"/>", entry.item.cate);
+        if let Some(author) = &entry.author {
+            let _ = writeln!(xml, "<author><name>{}</name></author>", escape_xml(author));
+        }
+        if let Some(content) = &entry.content {
+            let _ = writeln!(xml, "<content type=\"html\">{}</content>", escape_xml(content));
+        }
+        xml.push_str("</entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
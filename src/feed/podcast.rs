@@ -0,0 +1,286 @@
+//! # 歌曲播客订阅源
+//!
+//! 将歌曲转换为播客风格的RSS 2.0订阅源，每首歌曲是一期带音频附件的节目，
+//! 可以在任意播客应用中收听MSR的作品。
+//!
+//! - 附件（`enclosure`）来自`SongData.source_url`
+//! - 节目封面（`itunes:image`）来自专辑的`cover_url`
+//! - 节目描述来自`AlbumDetailData.intro`
+//!
+//! 支持按专辑或按艺术家生成订阅源。
+
+use crate::artist::artist_key;
+use crate::client::remote::RemoteApiClient;
+use crate::dto::{AlbumDetailData, SongData};
+use crate::error::AppError;
+use crate::feed::{FETCH_CONCURRENCY, SITE_URL, escape_xml, fetch_songs};
+use futures::{StreamExt, stream};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+/// 播客频道信息
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PodcastChannel {
+    /// 频道标题
+    pub title: String,
+
+    /// 频道描述
+    pub description: String,
+
+    /// 频道对应的网页地址
+    pub link: String,
+
+    /// 频道作者
+    pub author: String,
+
+    /// 频道封面图片URL
+    pub image: Option<String>,
+
+    /// 订阅源自身的访问地址，用于`rel="self"`链接
+    pub self_link: Option<String>,
+}
+
+/// 播客中的一期节目（一首歌曲）
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PodcastEpisode {
+    /// 歌曲详情
+    pub song: SongData,
+
+    /// 所属专辑名称
+    pub album_name: String,
+
+    /// 所属专辑封面URL
+    pub cover_url: String,
+
+    /// 所属专辑简介
+    pub intro: String,
+}
+
+impl PodcastEpisode {
+    /// 使用歌曲详情和所属专辑详情创建节目
+    pub fn new(song: SongData, album: &AlbumDetailData) -> Self {
+        Self {
+            song,
+            album_name: album.name.clone(),
+            cover_url: album.cover_url.clone(),
+            intro: album.intro.clone(),
+        }
+    }
+
+    /// 基于歌曲cid生成的稳定GUID
+    pub fn guid(&self) -> String {
+        format!("msr-song-{}", self.song.id)
+    }
+
+    /// 歌曲在MSR官网上的链接
+    pub fn link(&self) -> String {
+        format!("{}/music/{}", SITE_URL, self.song.id)
+    }
+}
+
+/// 根据音频URL的扩展名推断MIME类型
+///
+/// # 示例
+///
+/// ```rust
+/// use easy_msr_api::feed::podcast::audio_mime_type;
+///
+/// assert_eq!(audio_mime_type("https://res01.hycdn.cn/xxx/xxx.wav"), "audio/wav");
+/// assert_eq!(audio_mime_type("https://res01.hycdn.cn/xxx/xxx.MP3?t=1"), "audio/mpeg");
+/// ```
+pub fn audio_mime_type(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let ext = path.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("wav") => "audio/wav",
+        Some("flac") => "audio/flac",
+        Some("ogg") => "audio/ogg",
+        Some("m4a" | "aac") => "audio/mp4",
+        _ => "audio/mpeg",
+    }
+}
+
+/// 拉取指定专辑的播客频道与节目列表
+///
+/// # 参数
+///
+/// * `client` - 远程API客户端
+/// * `cid` - 专辑的唯一标识符
+///
+/// # 返回
+///
+/// 返回以专辑信息填充的频道，以及按专辑曲目顺序排列的节目
+pub async fn fetch_album(
+    client: &RemoteApiClient,
+    cid: String,
+) -> Result<(PodcastChannel, Vec<PodcastEpisode>), AppError> {
+    let album = client.get_album_detail(cid).await?.data;
+    let ids = album.songs.iter().map(|s| s.id.clone()).collect();
+    let episodes = fetch_songs(client, ids)
+        .await
+        .into_iter()
+        .map(|song| PodcastEpisode::new(song, &album))
+        .collect();
+
+    let channel = PodcastChannel {
+        title: album.name.clone(),
+        description: album.intro.clone(),
+        link: format!("{}/music", SITE_URL),
+        author: "塞壬唱片-MSR".into(),
+        image: Some(album.cover_url.clone()),
+        self_link: None,
+    };
+    Ok((channel, episodes))
+}
+
+/// 拉取指定艺术家的播客频道与节目列表
+///
+/// 通过`get_all_songs`筛选出艺术家列表中包含`artist`的歌曲（名称按
+/// [`artist_key`]规范化后比较），并拉取相关专辑详情以补充封面和简介。
+/// 拉取失败的歌曲或专辑会被跳过并记录警告，所属专辑拉取失败的歌曲不会出现在节目列表中。
+///
+/// # 参数
+///
+/// * `client` - 远程API客户端
+/// * `artist` - 艺术家名称
+///
+/// # 返回
+///
+/// 返回艺术家频道与节目列表；没有找到该艺术家的歌曲时返回`AppError::NotFound`
+pub async fn fetch_artist(
+    client: &RemoteApiClient,
+    artist: &str,
+) -> Result<(PodcastChannel, Vec<PodcastEpisode>), AppError> {
+    let artist = artist.trim();
+    let key = artist_key(artist);
+    let songs: Vec<_> = client
+        .get_all_songs()
+        .await?
+        .data
+        .list
+        .into_iter()
+        .filter(|song| song.artists.iter().any(|a| artist_key(a) == key))
+        .collect();
+    if songs.is_empty() {
+        return Err(AppError::NotFound);
+    }
+
+    let album_ids: BTreeSet<_> = songs.iter().map(|s| s.album_id.clone()).collect();
+    let albums: HashMap<_, _> = stream::iter(album_ids)
+        .map(|id| async move {
+            client
+                .get_album_detail(id.clone())
+                .await
+                .inspect_err(|e| tracing::warn!("拉取专辑详情失败 cid={}: {}", id, e))
+                .ok()
+                .map(|resp| (id, resp.data))
+        })
+        .buffer_unordered(FETCH_CONCURRENCY)
+        .filter_map(|album| async move { album })
+        .collect()
+        .await;
+
+    let ids = songs.into_iter().map(|s| s.id).collect();
+    let episodes: Vec<_> = fetch_songs(client, ids)
+        .await
+        .into_iter()
+        .filter_map(|song| {
+            let album = albums.get(&song.album_id)?;
+            Some(PodcastEpisode::new(song, album))
+        })
+        .collect();
+
+    let channel = PodcastChannel {
+        title: format!("{} - 塞壬唱片-MSR", artist),
+        description: format!("{}参与的塞壬唱片作品", artist),
+        link: format!("{}/music", SITE_URL),
+        author: artist.to_string(),
+        image: episodes.first().map(|e| e.cover_url.clone()),
+        self_link: None,
+    };
+    Ok((channel, episodes))
+}
+
+/// 生成播客风格的RSS 2.0订阅源
+///
+/// 没有音频URL的歌曲不会出现在订阅源中。
+///
+/// # 示例
+///
+/// ```rust
+/// use easy_msr_api::dto::{AlbumDetailData, SongData};
+/// use easy_msr_api::feed::podcast::{PodcastChannel, PodcastEpisode, render_rss};
+///
+/// let album = AlbumDetailData {
+///     name: "Little Wish".into(),
+///     intro: "一触即碎的肥皂泡".into(),
+///     cover_url: "https://web.hycdn.cn/siren/pic/a.jpg".into(),
+///     ..Default::default()
+/// };
+/// let song = SongData {
+///     id: "953953".into(),
+///     name: "Little Wish".into(),
+///     source_url: Some("https://res01.hycdn.cn/a.wav".into()),
+///     artists: vec!["塞壬唱片-MSR".into()],
+///     ..Default::default()
+/// };
+/// let xml = render_rss(&PodcastChannel::default(), &[PodcastEpisode::new(song, &album)]);
+/// assert!(xml.contains(r#"<enclosure url="https://res01.hycdn.cn/a.wav" length="0" type="audio/wav"/>"#));
+/// assert!(xml.contains(r#"<itunes:image href="https://web.hycdn.cn/siren/pic/a.jpg"/>"#));
+/// assert!(xml.contains("<description>一触即碎的肥皂泡</description>"));
+/// ```
+pub fn render_rss(channel: &PodcastChannel, episodes: &[PodcastEpisode]) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(concat!(
+        "<rss version=\"2.0\"",
+        " xmlns:atom=\"http://www.w3.org/2005/Atom\"",
+        " xmlns:itunes=\"http://www.itunes.com/dtds/podcast-1.0.dtd\">\n"
+    ));
+    xml.push_str("<channel>\n");
+    let _ = writeln!(xml, "<title>{}</title>", escape_xml(&channel.title));
+    let _ = writeln!(xml, "<link>{}</link>", escape_xml(&channel.link));
+    let _ = writeln!(xml, "<description>{}</description>", escape_xml(&channel.description));
+    xml.push_str("<language>zh-cn</language>\n");
+    if let Some(self_link) = &channel.self_link {
+        let _ = writeln!(
+            xml,
+            "<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>",
+            escape_xml(self_link)
+        );
+    }
+    let _ = writeln!(xml, "<itunes:author>{}</itunes:author>", escape_xml(&channel.author));
+    if let Some(image) = &channel.image {
+        let _ = writeln!(xml, "<itunes:image href=\"{}\"/>", escape_xml(image));
+    }
+    xml.push_str("<itunes:category text=\"Music\"/>\n");
+    xml.push_str("<itunes:explicit>false</itunes:explicit>\n");
+
+    for episode in episodes {
+        let Some(source_url) = &episode.song.source_url else {
+            continue;
+        };
+        xml.push_str("<item>\n");
+        let _ = writeln!(xml, "<title>{}</title>", escape_xml(&episode.song.name));
+        let _ = writeln!(xml, "<link>{}</link>", escape_xml(&episode.link()));
+        let _ = writeln!(xml, "<guid isPermaLink=\"false\">{}</guid>", escape_xml(&episode.guid()));
+        let _ = writeln!(xml, "<description>{}</description>", escape_xml(&episode.intro));
+        let _ = writeln!(
+            xml,
+            "<enclosure url=\"{}\" length=\"0\" type=\"{}\"/>",
+            escape_xml(source_url),
+            audio_mime_type(source_url)
+        );
+        let _ = writeln!(
+            xml,
+            "<itunes:author>{}</itunes:author>",
+            escape_xml(&episode.song.artists.join(", "))
+        );
+        let _ = writeln!(xml, "<itunes:subtitle>{}</itunes:subtitle>", escape_xml(&episode.album_name));
+        let _ = writeln!(xml, "<itunes:image href=\"{}\"/>", escape_xml(&episode.cover_url));
+        xml.push_str("</item>\n");
+    }
+
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
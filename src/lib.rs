@@ -12,7 +12,7 @@
 //! 
 //! ### 1. 使用默认客户端(推荐)
 //! 
-//! ```rust
+//! ```rust,no_run
 //! use easy_msr_api::MSRApiClient;
 //! 
 //! #[tokio::main]
@@ -29,7 +29,7 @@
 //! 
 //! ### 2. 作为库直接调用API
 //! 
-//! ```rust
+//! ```rust,no_run
 //! use easy_msr_api::client::remote::RemoteApiClient;
 //! 
 //! #[tokio::main]
@@ -46,7 +46,7 @@
 //! ```
 //! ### 3. 作为Web服务使用（需要启用web feature）
 //! 
-//! ```rust
+//! ```rust,ignore
 //! use easy_msr_api::{client::remote::RemoteApiClient, web};
 //! use std::net::Ipv4Addr;
 //! 
@@ -70,24 +70,44 @@
 //! 
 //! - **default**: 无额外功能，仅包含核心API封装
 //! - **web**: 启用Web路由和Swagger UI界面支持
+//! - **metrics**: 启用Prometheus指标，启用`web`时通过`GET /metrics`暴露
+//! - **otel**: 启用OpenTelemetry链路导出（OTLP）与W3C Trace Context传播
 //! 
 //! ## 模块结构
 //! 
+//! - [`artist`] - 艺术家索引
+//! - [`catalog`] - 曲库快照
 //! - [`client`] - API客户端实现
 //! - [`config`] - 配置管理
 //! - [`error`] - 错误处理
+//! - [`feed`] - RSS/Atom订阅源生成
+//! - [`playlist`] - M3U8/XSPF/PLS播放列表生成
+//! - [`search`] - 基于曲库快照的本地全文搜索
+//! - [`tasks`] - 后台任务与优雅退出
 //! - Web路由层（需要启用 `web` feature）
 #![cfg_attr(feature = "web", doc = "- [`web`] - Web 路由层")]
+#![cfg_attr(feature = "metrics", doc = "- [`metrics`] - Prometheus指标")]
+#![cfg_attr(feature = "otel", doc = "- [`telemetry`] - OpenTelemetry链路追踪")]
 
+pub mod artist;
+pub mod catalog;
 pub mod client;
 pub mod config;
 pub mod error;
 pub mod dto;
+pub mod feed;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod playlist;
+pub mod search;
+pub mod tasks;
+#[cfg(feature = "otel")]
+pub mod telemetry;
 
 #[cfg(feature = "web")]
 pub mod web;
 
-use crate::{client::remote::RemoteApiClient, dto::*, error::AppError};
+use crate::{catalog::Catalog, client::remote::RemoteApiClient, dto::*, error::AppError};
 
 /// 默认的MSR API客户端，使用官方API地址
 /// 
@@ -132,6 +152,11 @@ impl MSRApiClient {
         }
     }
 
+    /// 获取任意上游接口的原始JSON数据，保留上游返回的全部字段
+    pub async fn get_raw(&self, path: &str, query: &[(&str, &str)]) -> Result<serde_json::Value, AppError> {
+        self.inner.get_raw(path, query).await
+    }
+
     /// 获取指定ID的歌曲详情
     pub async fn get_song(&self, id: String) -> Result<SongResp, AppError> {
         self.inner.get_song(id).await
@@ -152,6 +177,23 @@ impl MSRApiClient {
         self.inner.get_album_detail(id).await
     }
 
+    /// 获取完整专辑数据（包含每首歌曲的完整详情）
+    /// 
+    /// 单首歌曲获取失败时，失败原因记录在对应曲目的`error`字段中。
+    pub async fn get_album_full(&self, id: String) -> Result<AlbumFullResp, AppError> {
+        self.inner.get_album_full(id).await
+    }
+
+    /// 批量获取歌曲详情，重复的cid只会请求一次
+    pub async fn get_songs_batch(&self, ids: Vec<String>) -> Result<SongBatchResp, AppError> {
+        self.inner.get_songs_batch(ids).await
+    }
+
+    /// 批量获取专辑信息，重复的cid只会请求一次
+    pub async fn get_albums_batch(&self, ids: Vec<String>) -> Result<AlbumBatchResp, AppError> {
+        self.inner.get_albums_batch(ids).await
+    }
+
     /// 获取所有专辑列表
     pub async fn get_all_albums(&self) -> Result<ApiResp<Vec<AllAlbumsItem>>, AppError> {
         self.inner.get_all_albums().await
@@ -212,6 +254,29 @@ impl MSRApiClient {
     ) -> Result<SearchNewsResp, AppError> {
         self.inner.search_news(keyword, last_cid).await
     }
+
+    /// 获取所有艺术家列表
+    pub async fn list_artists(&self) -> Result<ArtistListResp, AppError> {
+        self.inner.list_artists().await
+    }
+
+    /// 获取指定艺术家参与的专辑和歌曲
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 艺术家名称，任意一种写法均可
+    pub async fn get_artist(&self, name: String) -> Result<ArtistResp, AppError> {
+        self.inner.get_artist(name).await
+    }
+
+    /// 拉取曲库快照，可用于构建本地搜索索引
+    /// 
+    /// # 参数
+    /// 
+    /// * `news_pages` - 拉取的新闻页数，为0时不拉取新闻
+    pub async fn fetch_catalog(&self, news_pages: usize) -> Result<Catalog, AppError> {
+        Catalog::fetch(&self.inner, news_pages).await
+    }
 }
 
 impl Default for MSRApiClient {
@@ -0,0 +1,249 @@
+//! # Prometheus指标
+//!
+//! 启用`metrics` feature后，客户端与Web路由会记录以下指标：
+//!
+//! - `msr_http_requests_total{route, method, status}` - Web路由处理的请求数
+//! - `msr_http_request_duration_seconds{route, method, status}` - Web路由的处理耗时
+//! - `msr_http_requests_in_flight{route}` - Web路由正在处理的请求数
+//! - `msr_upstream_requests_total{path, outcome}` - 发往上游的请求数，`outcome`为结果分类（见下文）
+//! - `msr_upstream_request_duration_seconds{path}` - 上游请求耗时
+//! - `msr_upstream_requests_in_flight` - 正在进行的上游请求数
+//! - `msr_upstream_coalesced_total{path}` - 与相同的并发请求合并、未访问上游的请求数
+//! - `msr_upstream_healthy{upstream}` - 上游是否健康（1/0）
+//! - `msr_circuit_open` - 熔断器是否打开（1/0）
+//! - `msr_cache_requests_total{result}` - 响应缓存的查找结果：`hit`、`stale`、`miss`或`bypass`
+//! - `msr_cache_entries` - 服务端缓存的条目数
+//! - `msr_api_key_requests_total{key, result}` - 各API密钥的请求数，`key`为密钥名称（密钥无效时为空），
+//!   `result`为`ok`、`unauthorized`、`rate_limited`或`quota_exceeded`
+//!
+//! 上游请求的结果分类：`ok`、`http_4xx`、`http_5xx`、`timeout`、`connect`、
+//! `rate_limited`、`decode`、`middleware`与`other`。
+//!
+//! 路径标签中的cid等数字段会被替换为`{cid}`，避免标签数量无限增长。
+//! 启用`web` feature时指标通过`GET /metrics`暴露，也可以调用[`render`]自行暴露。
+
+use crate::error::AppError;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// 所有指标
+#[cfg_attr(not(feature = "web"), allow(dead_code))]
+struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+    http_in_flight: IntGaugeVec,
+    upstream_requests: IntCounterVec,
+    upstream_duration: HistogramVec,
+    upstream_in_flight: IntGauge,
+    upstream_coalesced: IntCounterVec,
+    upstream_healthy: IntGaugeVec,
+    circuit_open: IntGauge,
+    cache_requests: IntCounterVec,
+    cache_entries: IntGauge,
+    api_key_requests: IntCounterVec,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
+    let registry = Registry::new();
+    macro_rules! register {
+        ($metric:expr) => {{
+            let metric = $metric.expect("指标定义无效");
+            registry.register(Box::new(metric.clone())).expect("指标重复注册");
+            metric
+        }};
+    }
+    Metrics {
+        http_requests: register!(IntCounterVec::new(
+            Opts::new("msr_http_requests_total", "Web路由处理的请求数"),
+            &["route", "method", "status"],
+        )),
+        http_duration: register!(HistogramVec::new(
+            HistogramOpts::new("msr_http_request_duration_seconds", "Web路由的处理耗时"),
+            &["route", "method", "status"],
+        )),
+        http_in_flight: register!(IntGaugeVec::new(
+            Opts::new("msr_http_requests_in_flight", "Web路由正在处理的请求数"),
+            &["route"],
+        )),
+        upstream_requests: register!(IntCounterVec::new(
+            Opts::new("msr_upstream_requests_total", "发往上游的请求数"),
+            &["path", "outcome"],
+        )),
+        upstream_duration: register!(HistogramVec::new(
+            HistogramOpts::new("msr_upstream_request_duration_seconds", "上游请求耗时"),
+            &["path"],
+        )),
+        upstream_in_flight: register!(IntGauge::new(
+            "msr_upstream_requests_in_flight",
+            "正在进行的上游请求数"
+        )),
+        upstream_coalesced: register!(IntCounterVec::new(
+            Opts::new("msr_upstream_coalesced_total", "与相同的并发请求合并的请求数"),
+            &["path"],
+        )),
+        upstream_healthy: register!(IntGaugeVec::new(
+            Opts::new("msr_upstream_healthy", "上游是否健康"),
+            &["upstream"],
+        )),
+        circuit_open: register!(IntGauge::new("msr_circuit_open", "熔断器是否打开")),
+        cache_requests: register!(IntCounterVec::new(
+            Opts::new("msr_cache_requests_total", "响应缓存的查找结果"),
+            &["result"],
+        )),
+        cache_entries: register!(IntGauge::new("msr_cache_entries", "服务端缓存的条目数")),
+        api_key_requests: register!(IntCounterVec::new(
+            Opts::new("msr_api_key_requests_total", "各API密钥的请求数"),
+            &["key", "result"],
+        )),
+        registry,
+    }
+});
+
+/// 以Prometheus文本格式输出所有指标
+///
+/// # 示例
+///
+/// ```rust
+/// let text = easy_msr_api::metrics::render();
+/// assert!(text.is_empty() || text.starts_with('#'));
+/// ```
+pub fn render() -> String {
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&METRICS.registry.gather(), &mut buffer) {
+        tracing::warn!("输出指标失败: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+/// 进行中的请求，释放时减少对应的计数
+pub(crate) struct InFlight(IntGauge);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+/// 记录Web路由开始处理一个请求
+#[cfg_attr(not(feature = "web"), allow(dead_code))]
+pub(crate) fn http_started(route: &str) -> InFlight {
+    let gauge = METRICS.http_in_flight.with_label_values(&[route]);
+    gauge.inc();
+    InFlight(gauge)
+}
+
+/// 记录Web路由处理完一个请求
+#[cfg_attr(not(feature = "web"), allow(dead_code))]
+pub(crate) fn http_finished(route: &str, method: &str, status: u16, elapsed: Duration) {
+    let status = status.to_string();
+    let labels = [route, method, status.as_str()];
+    METRICS.http_requests.with_label_values(&labels).inc();
+    METRICS
+        .http_duration
+        .with_label_values(&labels)
+        .observe(elapsed.as_secs_f64());
+}
+
+/// 记录开始一个上游请求
+pub(crate) fn upstream_started() -> InFlight {
+    let gauge = METRICS.upstream_in_flight.clone();
+    gauge.inc();
+    InFlight(gauge)
+}
+
+/// 记录一个上游请求的结果
+pub(crate) fn upstream_finished<T>(path: &str, result: &Result<T, AppError>, elapsed: Duration) {
+    let path = path_label(path);
+    METRICS
+        .upstream_requests
+        .with_label_values(&[path.as_str(), outcome(result)])
+        .inc();
+    METRICS
+        .upstream_duration
+        .with_label_values(&[path.as_str()])
+        .observe(elapsed.as_secs_f64());
+}
+
+/// 记录一个被合并的请求
+pub(crate) fn upstream_coalesced(path: &str) {
+    METRICS
+        .upstream_coalesced
+        .with_label_values(&[path_label(path).as_str()])
+        .inc();
+}
+
+/// 更新上游健康状态与熔断器状态
+#[cfg_attr(not(feature = "web"), allow(dead_code))]
+pub(crate) fn upstream_health<'a>(upstreams: impl IntoIterator<Item = (&'a str, bool)>, circuit_open: bool) {
+    for (upstream, healthy) in upstreams {
+        METRICS
+            .upstream_healthy
+            .with_label_values(&[upstream])
+            .set(i64::from(healthy));
+    }
+    METRICS.circuit_open.set(i64::from(circuit_open));
+}
+
+/// 记录一次响应缓存的查找结果
+#[cfg_attr(not(feature = "web"), allow(dead_code))]
+pub(crate) fn cache_lookup(result: &str) {
+    METRICS.cache_requests.with_label_values(&[result]).inc();
+}
+
+/// 更新服务端缓存的条目数
+#[cfg_attr(not(feature = "web"), allow(dead_code))]
+pub(crate) fn cache_entries(entries: usize) {
+    METRICS.cache_entries.set(entries as i64);
+}
+
+/// 记录一次API密钥认证的结果
+#[cfg_attr(not(feature = "web"), allow(dead_code))]
+pub(crate) fn api_key_request(key: &str, result: &str) {
+    METRICS.api_key_requests.with_label_values(&[key, result]).inc();
+}
+
+/// 上游请求的结果分类
+fn outcome<T>(result: &Result<T, AppError>) -> &'static str {
+    match result {
+        Ok(_) => "ok",
+        Err(error) => classify(error),
+    }
+}
+
+/// 错误的分类
+fn classify(error: &AppError) -> &'static str {
+    match error {
+        AppError::Remote(e) if e.is_timeout() => "timeout",
+        AppError::Remote(e) if e.is_connect() => "connect",
+        AppError::Remote(e) if e.is_decode() => "decode",
+        AppError::Remote(e) => match e.status() {
+            Some(status) if status.is_client_error() => "http_4xx",
+            Some(status) if status.is_server_error() => "http_5xx",
+            _ => "other",
+        },
+        AppError::RateLimited { .. } => "rate_limited",
+        AppError::Decode(_) => "decode",
+        AppError::Middleware(_) => "middleware",
+        AppError::Coalesced(inner) => classify(inner),
+        _ => "other",
+    }
+}
+
+/// 将上游路径中包含数字的段替换为`{cid}`，如`album/3888/detail`转换为`album/{cid}/detail`
+fn path_label(path: &str) -> String {
+    path.trim_start_matches('/')
+        .split('/')
+        .map(|segment| {
+            if segment.bytes().any(|b| b.is_ascii_digit()) {
+                "{cid}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,315 @@
+//! # 播放列表生成模块
+//!
+//! 将专辑详情或任意歌曲列表转换为常见的播放列表格式，便于在VLC、
+//! 车载播放器等设备中直接播放。
+//!
+//! 支持的格式：
+//!
+//! - 扩展M3U8（`#EXTINF`包含艺术家与歌曲名）
+//! - XSPF（包含标题、创作者、专辑与封面）
+//! - PLS
+//!
+//! 曲目地址可以使用上游的`source_url`，也可以使用代理服务的地址
+//! （`{base}/song/{cid}/audio`），见[`UrlMode`]。
+
+use crate::client::remote::RemoteApiClient;
+use crate::dto::{AlbumDetailData, SongData};
+use crate::error::AppError;
+use crate::feed::{escape_xml, fetch_songs};
+use std::fmt::Write;
+
+/// 曲目地址的生成方式
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum UrlMode {
+    /// 直接使用上游返回的`source_url`
+    #[default]
+    Upstream,
+
+    /// 使用代理服务地址，参数为代理服务的基础URL（如`http://localhost:8080`）
+    Proxy(String),
+}
+
+impl UrlMode {
+    /// 生成歌曲的播放地址，歌曲没有音频URL时返回`None`
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use easy_msr_api::dto::SongData;
+    /// use easy_msr_api::playlist::UrlMode;
+    ///
+    /// let song = SongData {
+    ///     id: "953953".into(),
+    ///     source_url: Some("https://res01.hycdn.cn/a.wav".into()),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(UrlMode::Upstream.track_url(&song).unwrap(), "https://res01.hycdn.cn/a.wav");
+    /// assert_eq!(
+    ///     UrlMode::Proxy("http://localhost:8080/".into()).track_url(&song).unwrap(),
+    ///     "http://localhost:8080/song/953953/audio"
+    /// );
+    /// ```
+    pub fn track_url(&self, song: &SongData) -> Option<String> {
+        let source_url = song.source_url.as_ref()?;
+        Some(match self {
+            UrlMode::Upstream => source_url.clone(),
+            UrlMode::Proxy(base) => format!(
+                "{}/song/{}/audio",
+                base.trim_end_matches('/'),
+                urlencoding::encode(&song.id)
+            ),
+        })
+    }
+}
+
+/// 播放列表中的曲目
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlaylistTrack {
+    /// 歌曲cid
+    pub id: String,
+
+    /// 歌曲名称
+    pub title: String,
+
+    /// 艺术家列表
+    pub artists: Vec<String>,
+
+    /// 所属专辑名称
+    pub album: Option<String>,
+
+    /// 封面图片URL
+    pub image: Option<String>,
+
+    /// 播放地址
+    pub url: String,
+}
+
+impl PlaylistTrack {
+    /// 曲目的显示名称，格式为`艺术家 - 歌曲名`
+    pub fn display_name(&self) -> String {
+        if self.artists.is_empty() {
+            self.title.clone()
+        } else {
+            format!("{} - {}", self.artists.join(", "), self.title)
+        }
+    }
+}
+
+/// 播放列表
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Playlist {
+    /// 播放列表标题
+    pub title: String,
+
+    /// 播放列表创作者
+    pub creator: Option<String>,
+
+    /// 播放列表封面图片URL
+    pub image: Option<String>,
+
+    /// 曲目列表
+    pub tracks: Vec<PlaylistTrack>,
+}
+
+impl Playlist {
+    /// 使用任意歌曲列表创建播放列表
+    ///
+    /// 没有音频URL的歌曲会被跳过。
+    ///
+    /// # 参数
+    ///
+    /// * `title` - 播放列表标题
+    /// * `songs` - 歌曲详情列表
+    /// * `mode` - 曲目地址的生成方式
+    pub fn from_songs<'a>(
+        title: impl Into<String>,
+        songs: impl IntoIterator<Item = &'a SongData>,
+        mode: &UrlMode,
+    ) -> Self {
+        let tracks = songs
+            .into_iter()
+            .filter_map(|song| {
+                Some(PlaylistTrack {
+                    id: song.id.clone(),
+                    title: song.name.clone(),
+                    artists: song.artists.clone(),
+                    album: None,
+                    image: None,
+                    url: mode.track_url(song)?,
+                })
+            })
+            .collect();
+
+        Self {
+            title: title.into(),
+            tracks,
+            ..Self::default()
+        }
+    }
+
+    /// 使用专辑详情及其歌曲详情创建播放列表
+    ///
+    /// 每个曲目都会带上专辑名称与封面，没有音频URL的歌曲会被跳过。
+    ///
+    /// # 参数
+    ///
+    /// * `album` - 专辑详情
+    /// * `songs` - 专辑中歌曲的详情
+    /// * `mode` - 曲目地址的生成方式
+    pub fn from_album(album: &AlbumDetailData, songs: &[SongData], mode: &UrlMode) -> Self {
+        let mut playlist = Self::from_songs(album.name.clone(), songs, mode);
+        playlist.creator = Some("塞壬唱片-MSR".into());
+        playlist.image = Some(album.cover_url.clone());
+        for track in &mut playlist.tracks {
+            track.album = Some(album.name.clone());
+            track.image = Some(album.cover_url.clone());
+        }
+        playlist
+    }
+
+    /// 生成扩展M3U8格式的播放列表
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use easy_msr_api::playlist::{Playlist, PlaylistTrack};
+    ///
+    /// let playlist = Playlist {
+    ///     title: "Little Wish".into(),
+    ///     tracks: vec![PlaylistTrack {
+    ///         title: "Little Wish".into(),
+    ///         artists: vec!["塞壬唱片-MSR".into()],
+    ///         url: "https://res01.hycdn.cn/a.wav".into(),
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// let m3u = playlist.to_m3u8();
+    /// assert!(m3u.starts_with("#EXTM3U\n"));
+    /// assert!(m3u.contains("#EXTINF:-1,塞壬唱片-MSR - Little Wish\nhttps://res01.hycdn.cn/a.wav\n"));
+    /// ```
+    pub fn to_m3u8(&self) -> String {
+        let mut out = String::from("#EXTM3U\n");
+        let _ = writeln!(out, "#PLAYLIST:{}", single_line(&self.title));
+        for track in &self.tracks {
+            let _ = writeln!(out, "#EXTINF:-1,{}", single_line(&track.display_name()));
+            if let Some(album) = &track.album {
+                let _ = writeln!(out, "#EXTALB:{}", single_line(album));
+            }
+            let _ = writeln!(out, "{}", single_line(&track.url));
+        }
+        out
+    }
+
+    /// 生成XSPF格式的播放列表
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use easy_msr_api::playlist::{Playlist, PlaylistTrack};
+    ///
+    /// let playlist = Playlist {
+    ///     title: "Little Wish".into(),
+    ///     tracks: vec![PlaylistTrack {
+    ///         title: "Little Wish".into(),
+    ///         artists: vec!["塞壬唱片-MSR".into()],
+    ///         album: Some("Little Wish".into()),
+    ///         url: "https://res01.hycdn.cn/a.wav".into(),
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// let xspf = playlist.to_xspf();
+    /// assert!(xspf.contains("<location>https://res01.hycdn.cn/a.wav</location>"));
+    /// assert!(xspf.contains("<creator>塞壬唱片-MSR</creator>"));
+    /// assert!(xspf.contains("<album>Little Wish</album>"));
+    /// ```
+    pub fn to_xspf(&self) -> String {
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n");
+        let _ = writeln!(xml, "<title>{}</title>", escape_xml(&self.title));
+        if let Some(creator) = &self.creator {
+            let _ = writeln!(xml, "<creator>{}</creator>", escape_xml(creator));
+        }
+        if let Some(image) = &self.image {
+            let _ = writeln!(xml, "<image>{}</image>", escape_xml(image));
+        }
+        xml.push_str("<trackList>\n");
+        for track in &self.tracks {
+            xml.push_str("<track>\n");
+            let _ = writeln!(xml, "<location>{}</location>", escape_xml(&track.url));
+            let _ = writeln!(xml, "<title>{}</title>", escape_xml(&track.title));
+            if !track.artists.is_empty() {
+                let _ = writeln!(xml, "<creator>{}</creator>", escape_xml(&track.artists.join(", ")));
+            }
+            if let Some(album) = &track.album {
+                let _ = writeln!(xml, "<album>{}</album>", escape_xml(album));
+            }
+            if let Some(image) = &track.image {
+                let _ = writeln!(xml, "<image>{}</image>", escape_xml(image));
+            }
+            xml.push_str("</track>\n");
+        }
+        xml.push_str("</trackList>\n</playlist>\n");
+        xml
+    }
+
+    /// 生成PLS格式的播放列表
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use easy_msr_api::playlist::{Playlist, PlaylistTrack};
+    ///
+    /// let playlist = Playlist {
+    ///     tracks: vec![PlaylistTrack {
+    ///         title: "Little Wish".into(),
+    ///         url: "https://res01.hycdn.cn/a.wav".into(),
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// let pls = playlist.to_pls();
+    /// assert!(pls.contains("File1=https://res01.hycdn.cn/a.wav\nTitle1=Little Wish\nLength1=-1\n"));
+    /// assert!(pls.contains("NumberOfEntries=1\nVersion=2\n"));
+    /// ```
+    pub fn to_pls(&self) -> String {
+        let mut out = String::from("[playlist]\n");
+        for (i, track) in self.tracks.iter().enumerate() {
+            let n = i + 1;
+            let _ = writeln!(out, "File{}={}", n, single_line(&track.url));
+            let _ = writeln!(out, "Title{}={}", n, single_line(&track.display_name()));
+            let _ = writeln!(out, "Length{}=-1", n);
+        }
+        let _ = writeln!(out, "NumberOfEntries={}", self.tracks.len());
+        out.push_str("Version=2\n");
+        out
+    }
+}
+
+/// 去掉换行符，避免破坏基于行的播放列表格式
+fn single_line(s: &str) -> String {
+    s.replace(['\r', '\n'], " ")
+}
+
+/// 拉取专辑详情及其所有歌曲，生成专辑播放列表
+///
+/// 拉取失败的歌曲会被跳过并记录警告。
+///
+/// # 参数
+///
+/// * `client` - 远程API客户端
+/// * `cid` - 专辑的唯一标识符
+/// * `mode` - 曲目地址的生成方式
+pub async fn fetch_album(
+    client: &RemoteApiClient,
+    cid: String,
+    mode: &UrlMode,
+) -> Result<Playlist, AppError> {
+    let album = client.get_album_detail(cid).await?.data;
+    let ids = album.songs.iter().map(|s| s.id.clone()).collect();
+    let songs = fetch_songs(client, ids).await;
+
+    Ok(Playlist::from_album(&album, &songs, mode))
+}
@@ -0,0 +1,516 @@
+//! # 本地全文搜索
+//!
+//! 基于[`Catalog`]快照构建的本地搜索索引，弥补上游`search`接口只覆盖专辑和新闻、
+//! 且只支持近似精确匹配的不足。
+//!
+//! 索引覆盖歌曲名、专辑名、专辑简介、艺术家、新闻标题和新闻正文，支持：
+//!
+//! - 忽略大小写、全角/半角和多余空白的匹配
+//! - 容错的模糊匹配（基于Damerau-Levenshtein距离）
+//! - 中文标题的全拼和首字母匹配（如`shengmingliu`、`sml`匹配“生命流”）
+//!
+//! 搜索结果按相关度排序，以[`SearchResult`]枚举的形式返回；
+//! [`SearchIndex::search_songs`]可以按歌曲名、艺术家和专辑分别筛选歌曲。
+
+use crate::catalog::Catalog;
+use crate::dto::{AlbumData, AllSongsItem, NewsDetailData, SongSearchItem, SongSearchQuery};
+use pinyin::ToPinyin;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// 模糊匹配的最低相似度
+const FUZZY_THRESHOLD: f64 = 0.7;
+
+/// 参与模糊匹配的查询词最短长度（字符数）
+const FUZZY_MIN_CHARS: usize = 3;
+
+/// 规范化文本，用于索引和查询
+///
+/// 将全角ASCII字符转换为半角，统一转换为小写，并合并连续空白。
+///
+/// # 示例
+///
+/// ```rust
+/// use easy_msr_api::search::normalize;
+///
+/// assert_eq!(normalize("  Ｌｉｔｔｌｅ　 Wish "), "little wish");
+/// ```
+pub fn normalize(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\u{3000}' => ' ',
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            c => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 去掉HTML标签，保留文本内容
+fn strip_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                out.push(' ');
+            }
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// 生成文本的拼音全拼与首字母，文本不包含汉字时返回`None`
+fn to_pinyin(text: &str) -> Option<(String, String)> {
+    if !text.to_pinyin().any(|p| p.is_some()) {
+        return None;
+    }
+
+    let mut full = String::new();
+    let mut initials = String::new();
+    let mut in_word = false;
+    for (c, p) in text.chars().zip(text.to_pinyin()) {
+        match p {
+            Some(p) => {
+                full.push_str(p.plain());
+                initials.push_str(p.first_letter());
+                in_word = false;
+            }
+            None if c.is_alphanumeric() => {
+                full.push(c);
+                if !in_word {
+                    initials.push(c);
+                }
+                in_word = true;
+            }
+            None => in_word = false,
+        }
+    }
+    Some((full, initials))
+}
+
+/// 搜索结果
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", content = "data", rename_all = "lowercase")]
+pub enum SearchResult {
+    /// 歌曲
+    Song(AllSongsItem),
+
+    /// 专辑
+    Album(AlbumData),
+
+    /// 新闻
+    News(NewsDetailData),
+}
+
+impl SearchResult {
+    /// 结果的名称（歌曲名、专辑名或新闻标题）
+    pub fn title(&self) -> &str {
+        match self {
+            SearchResult::Song(song) => &song.name,
+            SearchResult::Album(album) => &album.name,
+            SearchResult::News(news) => &news.title,
+        }
+    }
+
+    /// 同分时的排序优先级
+    fn rank(&self) -> u8 {
+        match self {
+            SearchResult::Song(_) => 0,
+            SearchResult::Album(_) => 1,
+            SearchResult::News(_) => 2,
+        }
+    }
+}
+
+/// 带相关度的搜索结果
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    /// 相关度，取值范围为`(0, 1]`，越大越相关
+    pub score: f64,
+
+    /// 匹配到的条目
+    pub result: SearchResult,
+}
+
+/// 字段的类别，用于按字段筛选
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    /// 歌曲名、专辑名或新闻标题
+    Title,
+
+    /// 艺术家
+    Artist,
+
+    /// 歌曲所属专辑的名称
+    Album,
+
+    /// 专辑简介或新闻正文
+    Body,
+}
+
+/// 索引中的一个字段
+#[derive(Debug, Clone)]
+struct Field {
+    /// 字段类别
+    kind: FieldKind,
+
+    /// 规范化后的文本
+    text: String,
+
+    /// 文本中的词
+    tokens: Vec<String>,
+
+    /// 拼音全拼与首字母
+    pinyin: Option<(String, String)>,
+
+    /// 字段权重
+    weight: f64,
+
+    /// 是否参与模糊匹配
+    fuzzy: bool,
+}
+
+impl Field {
+    /// 短文本字段，支持拼音和模糊匹配
+    fn title(kind: FieldKind, text: &str, weight: f64) -> Self {
+        let text = normalize(text);
+        let pinyin = to_pinyin(&text);
+        Self {
+            kind,
+            tokens: tokenize(&text),
+            text,
+            pinyin,
+            weight,
+            fuzzy: true,
+        }
+    }
+
+    /// 长文本字段，只支持子串匹配
+    fn body(text: &str, weight: f64) -> Self {
+        Self {
+            kind: FieldKind::Body,
+            text: normalize(text),
+            tokens: Vec::new(),
+            pinyin: None,
+            weight,
+            fuzzy: false,
+        }
+    }
+
+    /// 计算查询词与字段的匹配分数（未加权）
+    fn score(&self, term: &str) -> f64 {
+        if self.text == term {
+            return 1.0;
+        }
+        if self.text.starts_with(term) {
+            return 0.9;
+        }
+        if self.text.contains(term) {
+            return 0.8;
+        }
+
+        let ascii_term = term.chars().all(|c| c.is_ascii_alphanumeric());
+        if let (true, Some((full, initials))) = (ascii_term, &self.pinyin) {
+            if full.starts_with(term) {
+                return 0.75;
+            }
+            if full.contains(term) {
+                return 0.7;
+            }
+            if term.len() >= 2 && initials.starts_with(term) {
+                return 0.65;
+            }
+            if term.len() >= 2 && initials.contains(term) {
+                return 0.6;
+            }
+        }
+
+        if !self.fuzzy || term.chars().count() < FUZZY_MIN_CHARS {
+            return 0.0;
+        }
+        let pinyin = self.pinyin.as_ref().filter(|_| ascii_term).map(|(full, _)| full);
+        let best = self
+            .tokens
+            .iter()
+            .chain(std::iter::once(&self.text))
+            .chain(pinyin)
+            .map(|candidate| strsim::normalized_damerau_levenshtein(term, candidate))
+            .fold(0.0, f64::max);
+        if best >= FUZZY_THRESHOLD { 0.6 * best } else { 0.0 }
+    }
+}
+
+/// 将规范化后的文本切分为词
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 索引中的一个条目
+#[derive(Debug, Clone)]
+struct Document {
+    result: SearchResult,
+    fields: Vec<Field>,
+}
+
+impl Document {
+    /// 计算查询词列表与条目的相关度，任一查询词不匹配时返回`None`
+    ///
+    /// `kind`不为`None`时只匹配该类别的字段。
+    fn score(&self, terms: &[String], kind: Option<FieldKind>) -> Option<f64> {
+        let mut total = 0.0;
+        for term in terms {
+            let best = self
+                .fields
+                .iter()
+                .filter(|field| kind.is_none_or(|kind| field.kind == kind))
+                .map(|field| field.weight * field.score(term))
+                .fold(0.0, f64::max);
+            if best <= 0.0 {
+                return None;
+            }
+            total += best;
+        }
+        Some(total / terms.len() as f64)
+    }
+}
+
+/// 本地搜索索引
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    docs: Vec<Document>,
+
+    /// 专辑cid到专辑名称的映射，用于补全歌曲搜索结果
+    album_names: HashMap<String, String>,
+}
+
+impl SearchIndex {
+    /// 从曲库快照构建搜索索引
+    ///
+    /// 歌曲会通过`album_id`关联专辑名称，使按专辑名搜索也能命中歌曲。
+    pub fn build(catalog: &Catalog) -> Self {
+        let mut docs = Vec::with_capacity(catalog.songs.len() + catalog.albums.len() + catalog.news.len());
+
+        for song in &catalog.songs {
+            let mut fields = vec![Field::title(FieldKind::Title, &song.name, 1.0)];
+            fields.extend(song.artists.iter().map(|a| Field::title(FieldKind::Artist, a, 0.8)));
+            if let Some(album) = catalog.album(&song.album_id) {
+                fields.push(Field::title(FieldKind::Album, &album.name, 0.6));
+            }
+            docs.push(Document {
+                result: SearchResult::Song(song.clone()),
+                fields,
+            });
+        }
+
+        for album in catalog.albums.values() {
+            let mut fields = vec![Field::title(FieldKind::Title, &album.name, 1.0)];
+            fields.extend(album.artists.iter().map(|a| Field::title(FieldKind::Artist, a, 0.8)));
+            fields.push(Field::body(&album.intro, 0.4));
+            docs.push(Document {
+                result: SearchResult::Album(album.clone()),
+                fields,
+            });
+        }
+
+        for news in &catalog.news {
+            docs.push(Document {
+                result: SearchResult::News(news.clone()),
+                fields: vec![
+                    Field::title(FieldKind::Title, &news.title, 0.9),
+                    Field::body(&strip_html(&news.content), 0.4),
+                ],
+            });
+        }
+
+        let album_names = catalog
+            .albums
+            .iter()
+            .map(|(id, album)| (id.clone(), album.name.clone()))
+            .collect();
+        Self { docs, album_names }
+    }
+
+    /// 索引中的条目数量
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    /// 索引是否为空
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// 搜索索引
+    ///
+    /// 查询会按空白切分为多个词，每个词都需要命中条目的某个字段；
+    /// 条目的相关度为各个词最佳得分的平均值。
+    ///
+    /// # 参数
+    ///
+    /// * `query` - 查询字符串
+    /// * `limit` - 返回结果的最大数量
+    ///
+    /// # 返回
+    ///
+    /// 按相关度从高到低排列的搜索结果
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use easy_msr_api::catalog::Catalog;
+    /// use easy_msr_api::dto::{AlbumData, AllSongsItem};
+    /// use easy_msr_api::search::{SearchIndex, SearchResult};
+    /// use std::collections::HashMap;
+    ///
+    /// let catalog = Catalog {
+    ///     songs: vec![AllSongsItem {
+    ///         id: "514512".into(),
+    ///         name: "生命流".into(),
+    ///         album_id: "7770".into(),
+    ///         artists: vec!["塞壬唱片-MSR".into()],
+    ///     }],
+    ///     albums: HashMap::from([(
+    ///         "7770".to_string(),
+    ///         AlbumData {
+    ///             id: "7770".into(),
+    ///             name: "Little Wish".into(),
+    ///             ..Default::default()
+    ///         },
+    ///     )]),
+    ///     ..Default::default()
+    /// };
+    /// let index = SearchIndex::build(&catalog);
+    ///
+    /// // 拼音与首字母
+    /// assert_eq!(index.search("shengmingliu", 10)[0].result.title(), "生命流");
+    /// assert_eq!(index.search("sml", 10)[0].result.title(), "生命流");
+    /// // 容错匹配
+    /// assert!(matches!(index.search("litle wihs", 10)[0].result, SearchResult::Album(_)));
+    /// ```
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let terms = tokenize(&normalize(query));
+        if terms.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let mut hits: Vec<SearchHit> = self
+            .docs
+            .iter()
+            .filter_map(|doc| {
+                doc.score(&terms, None).map(|score| SearchHit {
+                    score,
+                    result: doc.result.clone(),
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.result.rank().cmp(&b.result.rank()))
+                .then_with(|| a.result.title().cmp(b.result.title()))
+        });
+        hits.truncate(limit);
+        hits
+    }
+
+    /// 按条件筛选歌曲
+    ///
+    /// 匹配规则与[`search`](Self::search)相同，各条件只匹配对应的字段：
+    ///
+    /// - `keyword`匹配歌曲名、任一艺术家或专辑名
+    /// - `artist`匹配任一艺术家
+    /// - `album`匹配专辑名，或与专辑cid完全相等
+    ///
+    /// 多个条件同时提供时需全部满足，空条件会被忽略；没有任何条件时返回空列表。
+    /// 结果按各条件相关度的平均值从高到低排列。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use easy_msr_api::catalog::Catalog;
+    /// use easy_msr_api::dto::{AllAlbumsItem, AllSongsItem, SongSearchQuery};
+    /// use easy_msr_api::search::SearchIndex;
+    ///
+    /// let songs = vec![AllSongsItem {
+    ///     id: "953953".into(),
+    ///     name: "Little Wish".into(),
+    ///     album_id: "3888".into(),
+    ///     artists: vec!["塞壬唱片-MSR".into()],
+    /// }];
+    /// let albums = vec![AllAlbumsItem {
+    ///     id: "3888".into(),
+    ///     name: "Little Wish".into(),
+    ///     ..Default::default()
+    /// }];
+    /// let index = SearchIndex::build(&Catalog::from_lists(songs, albums));
+    ///
+    /// let query = SongSearchQuery { artist: Some("ｍｓｒ".into()), ..Default::default() };
+    /// assert_eq!(index.search_songs(&query)[0].album_name, "Little Wish");
+    /// let query = SongSearchQuery { album: Some("3888".into()), ..Default::default() };
+    /// assert_eq!(index.search_songs(&query).len(), 1);
+    /// ```
+    pub fn search_songs(&self, query: &SongSearchQuery) -> Vec<SongSearchItem> {
+        let conditions: Vec<_> = [
+            (&query.keyword, None),
+            (&query.artist, Some(FieldKind::Artist)),
+            (&query.album, Some(FieldKind::Album)),
+        ]
+        .into_iter()
+        .filter_map(|(q, kind)| {
+            let text = normalize(q.as_deref()?);
+            let terms = tokenize(&text);
+            (!terms.is_empty()).then_some((text, terms, kind))
+        })
+        .collect();
+        if conditions.is_empty() {
+            return Vec::new();
+        }
+
+        let mut hits: Vec<(f64, &AllSongsItem)> = self
+            .docs
+            .iter()
+            .filter_map(|doc| {
+                let SearchResult::Song(song) = &doc.result else {
+                    return None;
+                };
+                let mut total = 0.0;
+                for (text, terms, kind) in &conditions {
+                    total += match kind {
+                        Some(FieldKind::Album) if *text == song.album_id => 1.0,
+                        kind => doc.score(terms, *kind)?,
+                    };
+                }
+                Some((total / conditions.len() as f64, song))
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.1.name.cmp(&b.1.name))
+        });
+        hits.into_iter()
+            .map(|(_, song)| SongSearchItem {
+                id: song.id.clone(),
+                name: song.name.clone(),
+                album_id: song.album_id.clone(),
+                album_name: self.album_names.get(&song.album_id).cloned().unwrap_or_default(),
+                artists: song.artists.clone(),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,89 @@
+//! # 后台任务
+//!
+//! 客户端与Web路由在后台运行的任务（缓存刷新、上游健康检查等）都通过[`Tasks`]启动，
+//! 以便在服务退出前停止轮询任务，并等待进行中的任务完成：
+//!
+//! - [`Tasks::spawn`]启动的任务会被记录，[`Tasks::shutdown`]会等待其完成
+//! - 轮询任务应在[`Tasks::stopping`]完成时退出
+//!
+//! 客户端持有一个[`Tasks`]，由它创建的路由也使用同一个实例，见
+//! [`RemoteApiClient::with_tasks`](crate::client::remote::RemoteApiClient::with_tasks)。
+//! 也可以通过[`Tasks::spawn`]启动自己的后台任务（如定期将曲库快照保存到磁盘），使其参与优雅退出。
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use easy_msr_api::client::remote::RemoteApiClient;
+//! use easy_msr_api::tasks::Tasks;
+//!
+//! # async fn demo() {
+//! let tasks = Tasks::new();
+//! let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string())
+//!     .with_tasks(tasks.clone());
+//! let stopping = tasks.clone();
+//! tasks.spawn(async move {
+//!     loop {
+//!         tokio::select! {
+//!             _ = tokio::time::sleep(Duration::from_secs(60)) => println!("轮询"),
+//!             _ = stopping.stopping() => break,
+//!         }
+//!     }
+//!     println!("退出前保存数据");
+//! });
+//! // ... 收到退出信号后
+//! tasks.shutdown(Duration::from_secs(10)).await;
+//! # }
+//! ```
+
+use std::future::Future;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+
+/// 一组后台任务
+///
+/// 克隆后的实例共享同一组任务，[`shutdown`](Self::shutdown)只影响通过这组实例启动的任务。
+#[derive(Debug, Clone, Default)]
+pub struct Tasks {
+    /// 正在运行的后台任务
+    tracker: TaskTracker,
+
+    /// 通知后台任务停止
+    stopping: CancellationToken,
+}
+
+impl Tasks {
+    /// 创建空的任务组
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 启动后台任务
+    ///
+    /// 任务会在[`shutdown`](Self::shutdown)时被等待；长期运行的任务应同时等待[`stopping`](Self::stopping)并及时退出。
+    pub fn spawn<F>(&self, task: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.tracker.spawn(task)
+    }
+
+    /// 调用[`shutdown`](Self::shutdown)后完成
+    pub async fn stopping(&self) {
+        self.stopping.cancelled().await
+    }
+
+    /// 通知后台任务停止，并等待所有后台任务完成
+    ///
+    /// 超过`timeout`仍未完成时放弃等待，返回`false`。
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        self.stopping.cancel();
+        self.tracker.close();
+        let finished = tokio::time::timeout(timeout, self.tracker.wait()).await.is_ok();
+        if !finished {
+            tracing::warn!("等待后台任务超时，仍有{}个任务未完成", self.tracker.len());
+        }
+        finished
+    }
+}
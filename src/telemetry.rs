@@ -0,0 +1,97 @@
+//! # OpenTelemetry链路追踪
+//!
+//! 启用`otel` feature后，可将`tracing`的span通过OTLP（HTTP/protobuf）导出到OpenTelemetry Collector，
+//! 并按W3C Trace Context（`traceparent`请求头）传播链路：
+//!
+//! - Web路由从请求头`traceparent`中提取上游链路，作为请求span的父级
+//! - 客户端发往MSR API的每个请求都会注入当前span的`traceparent`
+//!
+//! 客户端方法的span带有`cid`、`keyword`等字段，上游请求与Web请求的span带有HTTP状态码。
+//! 未调用[`init`]时不会导出也不会传播链路。
+//!
+//! ```rust,no_run
+//! use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+//!
+//! # fn demo() -> Result<(), easy_msr_api::error::AppError> {
+//! let telemetry = easy_msr_api::telemetry::init("http://localhost:4318", "easy-msr-api")?;
+//! tracing_subscriber::registry()
+//!     .with(tracing_subscriber::fmt::layer())
+//!     .with(telemetry.layer())
+//!     .init();
+//! // ... 运行服务
+//! telemetry.shutdown();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::AppError;
+use opentelemetry::global;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_http::HeaderInjector;
+use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+
+/// 已初始化的链路导出
+///
+/// 退出前应调用[`Telemetry::shutdown`]，导出尚未发送的span。
+pub struct Telemetry {
+    provider: SdkTracerProvider,
+}
+
+/// 初始化OTLP链路导出与W3C Trace Context传播
+///
+/// # 参数
+///
+/// * `endpoint` - OTLP/HTTP接收地址，如`http://localhost:4318`，span发送到其下的`/v1/traces`
+/// * `service_name` - 上报的服务名
+pub fn init(endpoint: &str, service_name: &str) -> Result<Telemetry, AppError> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_protocol(Protocol::HttpBinary)
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()
+        .map_err(|e| AppError::Config(format!("创建OTLP导出器失败: {}", e)))?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name.to_string()).build())
+        .build();
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    Ok(Telemetry { provider })
+}
+
+impl Telemetry {
+    /// 将`tracing`的span导出为OpenTelemetry span的订阅层
+    pub fn layer<S>(&self) -> OpenTelemetryLayer<S, SdkTracer>
+    where
+        S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(self.provider.tracer(env!("CARGO_PKG_NAME")))
+    }
+
+    /// 导出尚未发送的span并关闭导出
+    pub fn shutdown(self) {
+        if let Err(e) = self.provider.shutdown() {
+            tracing::warn!("关闭OTLP导出失败: {}", e);
+        }
+    }
+}
+
+/// 将当前span的链路上下文注入请求头
+pub(crate) fn with_context(mut request: reqwest::Request) -> reqwest::Request {
+    let context = tracing::Span::current().context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(request.headers_mut()))
+    });
+    request
+}
+
+/// 从请求头中提取链路上下文，作为`span`的父级
+#[cfg(feature = "web")]
+pub(crate) fn set_parent(span: &tracing::Span, headers: &axum::http::HeaderMap) {
+    let context =
+        global::get_text_map_propagator(|propagator| propagator.extract(&opentelemetry_http::HeaderExtractor(headers)));
+    let _ = span.set_parent(context);
+}
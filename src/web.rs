@@ -7,11 +7,22 @@
 //! ### 歌曲相关
 //! - `GET /song/{cid}` - 获取歌曲详情
 //! - `GET /songs` - 获取所有歌曲列表
+//! - `POST /songs/batch` - 批量获取歌曲详情
+//! - `GET /song/{cid}/audio` - 重定向到歌曲音频文件
 //! 
 //! ### 专辑相关
 //! - `GET /album/{cid}/data` - 获取专辑信息
 //! - `GET /album/{cid}/detail` - 获取专辑详情（含歌曲列表）
+//! - `GET /album/{cid}/full` - 获取完整专辑数据（含每首歌曲的完整详情）
 //! - `GET /albums` - 获取所有专辑列表
+//! - `POST /albums/batch` - 批量获取专辑信息
+//! - `GET /album/{cid}/playlist.m3u8` - 专辑M3U8播放列表
+//! - `GET /album/{cid}/playlist.xspf` - 专辑XSPF播放列表
+//! - `GET /album/{cid}/playlist.pls` - 专辑PLS播放列表
+//! 
+//! ### 艺术家相关
+//! - `GET /artists` - 获取所有艺术家列表
+//! - `GET /artist/{name}` - 获取艺术家参与的专辑和歌曲
 //! 
 //! ### 新闻相关
 //! - `GET /news` - 获取所有新闻列表
@@ -21,19 +32,157 @@
 //! - `GET /search` - 综合搜索（专辑和新闻）
 //! - `GET /search/album` - 搜索专辑
 //! - `GET /search/news` - 搜索新闻
+//! - `GET /search/song` - 搜索歌曲（本地索引）
+//! - `GET /search/all` - 综合搜索（专辑、新闻和歌曲）
+//! 
+//! ### 订阅源
+//! - `GET /feeds/news.rss` - 新闻RSS 2.0订阅源
+//! - `GET /feeds/news.atom` - 新闻Atom订阅源
+//! - `GET /feeds/album/{cid}.rss` - 专辑播客订阅源
+//! - `GET /feeds/artist/{name}.rss` - 艺术家播客订阅源
 //! 
 //! ### 其他
 //! - `GET /fontset` - 获取字体配置
+//! - `GET /health` - 获取服务健康状态（上游状态、熔断器状态与请求合并统计）
+//! - `GET /usage` - 当前API密钥的用量（需通过[`RouterOptions::auth`]启用API密钥认证）
+//! 
+//! ### 运维接口（需通过[`RouterOptions::probes`]启用）
+//! - `GET /healthz` - 存活检查
+//! - `GET /readyz` - 就绪检查（缓存的上游可达性检查结果）
+//! - `GET /version` - 版本号、git提交哈希与配置摘要
+//! 
+//! ### 指标（需要启用`metrics` feature）
+//! - `GET /metrics` - Prometheus指标，见[`crate::metrics`]
 //! 
 //! ### Swagger UI
 //! - `GET /swagger-ui/` - Swagger UI文档界面
 //! - `GET /api-docs/openapi.json` - OpenAPI规范文档
+//! 
+//! 访问了上游的响应会带有`X-Upstream`响应头，标明实际提供数据的上游地址。
+//! 所有响应都带有`X-Request-Id`响应头（沿用请求头中的值或新生成），错误响应体中的`requestId`与之相同。
+//! 
+//! ## 响应缓存
+//! 
+//! 设置[`RouterOptions::cache`]后，为GET请求的成功响应添加`ETag`、`Last-Modified`与`Cache-Control`响应头，
+//! 支持条件请求（`304 Not Modified`），并在服务端缓存响应，过期后先返回旧响应再在后台刷新。
+//! 缓存策略按路由配置，详见[`cache`]模块。
+//! 
+//! ## 透传模式
+//! 
+//! 默认情况下，直接对应上游接口的路由会将上游数据反序列化为DTO后重新序列化，
+//! DTO中未建模的字段会丢失。通过[`RouterOptions::passthrough`]启用透传后，
+//! 这些路由会原样转发上游响应体（仍会按DTO校验，格式不符时返回错误）。
+//! 
+//! ## 路由前缀、CORS、压缩与安全响应头
+//! 
+//! 通过[`RouterOptions::path_prefix`]可将所有路由（含Swagger UI与OpenAPI文档）挂载到前缀下，
+//! OpenAPI文档的`servers`也会带上该前缀。以下功能默认关闭，可分别启用：
+//! 
+//! - [`RouterOptions::cors`]：按来源白名单响应CORS预检请求
+//! - [`RouterOptions::compression`]：按`Accept-Encoding`以gzip、Brotli或zstd压缩响应，适合`/songs`、`/albums`等较大的响应
+//! - [`RouterOptions::security_headers`]：添加`X-Content-Type-Options`、`X-Frame-Options`等安全响应头
+//! 
+//! ## API密钥认证
+//! 
+//! 设置[`RouterOptions::auth`]后，API路由需要在请求头`X-API-Key`或查询参数`api_key`中携带有效的API密钥，
+//! 每个密钥有各自的速率限制与每日配额；Swagger UI、运维接口与指标不需要密钥。详见[`auth`]模块。
+//! 
+//! ## 配置热加载
+//! 
+//! 设置[`RouterOptions::live`]后，响应缓存与CORS允许的来源从[`LiveConfig`]中读取，
+//! 调用[`LiveConfig::reload`]后立即生效，见[`crate::config::reload`]。
 
 use crate::client::remote::RemoteApiClient;
+use crate::config::reload::LiveConfig;
+use crate::config::{AuthConfig, CacheConfig, CompressionConfig, ConfigSummary, CorsConfig, SecurityHeadersConfig};
 use axum::Router;
+use std::time::Duration;
 
+pub mod auth;
+pub mod cache;
 pub mod docs;
 pub mod handler;
+pub mod middleware;
+pub mod state;
+
+/// 路由选项
+#[derive(Debug, Clone, Default)]
+pub struct RouterOptions {
+    /// 透传模式
+    /// 
+    /// 启用后，直接对应上游接口的路由原样转发上游响应体，
+    /// 保留DTO未建模的字段以及原有的键顺序和数字格式
+    pub passthrough: bool,
+
+    /// 响应缓存配置
+    /// 
+    /// 默认为`None`，不添加缓存响应头，也不在服务端缓存响应；可设置为`Some(CacheConfig::default())`启用
+    pub cache: Option<CacheConfig>,
+
+    /// 运维接口选项
+    /// 
+    /// 默认为`None`，设置后添加`/healthz`、`/readyz`与`/version`路由。
+    /// 这些路由不经过响应缓存，响应也不带`X-Upstream`响应头
+    pub probes: Option<ProbeOptions>,
+
+    /// 路由前缀
+    /// 
+    /// 默认为`None`，设置后（如`/msr`）所有路由（包括Swagger UI、运维接口与指标）都挂载在该前缀下
+    pub path_prefix: Option<String>,
+
+    /// 对外的访问地址
+    /// 
+    /// 默认为`None`，设置后（如`https://msr.example.com`，不含路由前缀）订阅源与播放列表中指向本服务的链接都以该地址开头；
+    /// 未设置时按请求头`Forwarded`、`X-Forwarded-Proto`、`X-Forwarded-Host`与`Host`推断，这些响应只允许客户端缓存
+    pub public_url: Option<String>,
+
+    /// CORS配置
+    /// 
+    /// 默认为`None`，不添加CORS响应头，浏览器中其他来源的页面无法调用接口
+    pub cors: Option<CorsConfig>,
+
+    /// 响应压缩配置
+    /// 
+    /// 默认为`None`，不压缩响应。服务端缓存保存未压缩的响应，每次响应时按请求头`Accept-Encoding`压缩
+    pub compression: Option<CompressionConfig>,
+
+    /// 安全响应头配置
+    /// 
+    /// 默认为`None`，不添加安全响应头
+    pub security_headers: Option<SecurityHeadersConfig>,
+
+    /// API密钥认证配置
+    /// 
+    /// 默认为`None`，任何人都可以调用接口；设置后API路由需要有效的API密钥，并添加`/usage`路由
+    pub auth: Option<AuthConfig>,
+
+    /// 可重新加载的配置
+    /// 
+    /// 默认为`None`，设置后忽略`cache`与`cors`，改为从中读取：响应缓存配置随重新加载生效；
+    /// 创建路由时启用了CORS才会添加CORS中间件，之后允许的来源随重新加载生效
+    pub live: Option<LiveConfig>,
+}
+
+/// 运维接口选项
+#[derive(Debug, Clone)]
+pub struct ProbeOptions {
+    /// 就绪检查结果的缓存时间
+    /// 
+    /// 首次请求`/readyz`时同步检查各上游；之后结果超过该时间时返回旧结果，同时在后台重新检查
+    pub readiness_ttl: Duration,
+
+    /// `/version`中展示的配置摘要
+    pub config: Option<ConfigSummary>,
+}
+
+impl Default for ProbeOptions {
+    fn default() -> Self {
+        Self {
+            readiness_ttl: Duration::from_secs(10),
+            config: None,
+        }
+    }
+}
 
 /// 创建包含Swagger UI的完整API路由
 /// 
@@ -49,7 +198,7 @@ pub mod handler;
 /// 
 /// # 示例
 /// 
-/// ```rust
+/// ```rust,no_run
 /// use easy_msr_api::{client::remote::RemoteApiClient, web};
 /// use std::net::Ipv4Addr;
 /// 
@@ -68,22 +217,144 @@ pub mod handler;
 /// }
 /// ```
 pub fn routes(client: RemoteApiClient) -> Router {
-    use axum::routing::get;
+    routes_with_options(client, RouterOptions::default())
+}
+
+/// 使用自定义选项创建包含Swagger UI的完整API路由
+/// 
+/// # 参数
+/// 
+/// * `client` - 远程API客户端实例
+/// * `options` - 路由选项
+/// 
+/// # 示例
+/// 
+/// ```rust
+/// use easy_msr_api::{client::remote::RemoteApiClient, web::{self, RouterOptions}};
+/// 
+/// let client = RemoteApiClient::new("https://monster-siren.hypergryph.com/api".to_string());
+/// let app = web::routes_with_options(
+///     client,
+///     RouterOptions {
+///         passthrough: true,
+///         ..Default::default()
+///     },
+/// );
+/// ```
+pub fn routes_with_options(client: RemoteApiClient, options: RouterOptions) -> Router {
+    use axum::routing::{get, post};
     use handler::*;
-    use utoipa_swagger_ui::SwaggerUi;
 
-    Router::new()
+    let cache = match &options.live {
+        Some(live) => Some(cache::ResponseCache::live(live.clone())),
+        None => options.cache.clone().map(cache::ResponseCache::new),
+    }
+    .map(|cache| cache.with_tasks(client.tasks().clone()));
+    let router = Router::new()
         .route("/song/{cid}", get(get_song))
+        .route("/song/{cid}/audio", get(get_song_audio))
         .route("/songs", get(get_all_songs))
+        .route("/songs/batch", post(get_songs_batch))
         .route("/album/{cid}/data", get(get_album))
         .route("/album/{cid}/detail", get(get_album_detail))
+        .route("/album/{cid}/full", get(get_album_full))
+        .route("/album/{cid}/playlist.m3u8", get(album_m3u8))
+        .route("/album/{cid}/playlist.xspf", get(album_xspf))
+        .route("/album/{cid}/playlist.pls", get(album_pls))
         .route("/albums", get(get_all_albums))
+        .route("/albums/batch", post(get_albums_batch))
+        .route("/artists", get(list_artists))
+        .route("/artist/{name}", get(get_artist))
         .route("/news", get(get_all_news))
         .route("/news/{cid}", get(get_news_detail))
         .route("/search", get(search))
         .route("/search/album", get(search_albums))
         .route("/search/news", get(search_news))
+        .route("/search/song", get(search_songs))
+        .route("/search/all", get(search_all))
         .route("/fontset", get(get_font))
-        .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", docs::api_doc()))
-        .with_state(client)
+        .route("/health", get(health))
+        .route("/feeds/news.rss", get(news_rss))
+        .route("/feeds/news.atom", get(news_atom))
+        .route("/feeds/album/{file}", get(album_podcast))
+        .route("/feeds/artist/{file}", get(artist_podcast))
+        .merge(swagger_ui(options.path_prefix.as_deref(), options.auth.as_ref()));
+    // 缓存位于X-Upstream之内，命中缓存的响应不带该响应头
+    let router = match cache {
+        Some(cache) => router.layer(axum::middleware::from_fn_with_state(cache, cache::cache_responses)),
+        None => router,
+    };
+    let router = router.layer(axum::middleware::from_fn(middleware::report_upstream));
+    // 认证位于缓存之外，命中缓存的请求同样计入用量；用量不经过缓存
+    let api_keys = options.auth.as_ref().map(auth::ApiKeys::new);
+    let router = match &api_keys {
+        Some(api_keys) => router
+            .route("/usage", get(usage))
+            .layer(axum::middleware::from_fn_with_state(api_keys.clone(), auth::authenticate)),
+        None => router,
+    };
+    let router = router.with_state(state::AppState::with_options(client.clone(), options.clone()));
+    #[cfg(feature = "metrics")]
+    let router = router.merge(Router::new().route("/metrics", get(metrics)).with_state(client.clone()));
+    let router = match options.probes {
+        Some(probes) => router.merge(
+            Router::new()
+                .route("/healthz", get(healthz))
+                .route("/readyz", get(readyz))
+                .route("/version", get(version))
+                .with_state(state::ProbeState::new(client, probes)),
+        ),
+        None => router,
+    };
+    let router = match &options.path_prefix {
+        Some(prefix) => Router::new().nest(prefix, router),
+        None => router,
+    };
+    #[cfg(feature = "metrics")]
+    let router = router.layer(axum::middleware::from_fn(middleware::track_metrics));
+    let router = match &options.compression {
+        Some(compression) => router.layer(middleware::compression_layer(compression)),
+        None => router,
+    };
+    let router = match &options.security_headers {
+        Some(security_headers) => router.layer(axum::middleware::from_fn_with_state(
+            middleware::SecurityHeaders::new(security_headers),
+            middleware::security_headers,
+        )),
+        None => router,
+    };
+    let router = match (&options.live, &options.cors) {
+        (Some(live), _) => match &live.load().cors {
+            Some(cors) => router.layer(middleware::live_cors_layer(cors, live.clone())),
+            None => router,
+        },
+        (None, Some(cors)) => router.layer(middleware::cors_layer(cors)),
+        (None, None) => router,
+    };
+    let router = router.layer(axum::middleware::from_fn(middleware::trace_request));
+    let router = match api_keys {
+        Some(api_keys) => router.layer(axum::middleware::from_fn_with_state(api_keys, auth::take_query_key)),
+        None => router,
+    };
+    router.layer(axum::middleware::from_fn(middleware::request_id))
+}
+
+/// Swagger UI路由，设置了路由前缀时文档地址与OpenAPI中的服务器地址都带有前缀
+/// 
+/// OpenAPI文档中的API密钥认证方式按`auth`调整，未启用认证时移除。
+fn swagger_ui(path_prefix: Option<&str>, auth: Option<&AuthConfig>) -> utoipa_swagger_ui::SwaggerUi {
+    use utoipa_swagger_ui::{Config, SwaggerUi};
+
+    let mut doc = docs::api_doc();
+    docs::apply_auth(&mut doc, auth);
+    let swagger_ui = SwaggerUi::new("/swagger-ui");
+    match path_prefix {
+        Some(prefix) => {
+            doc.servers = Some(vec![utoipa::openapi::Server::new(prefix)]);
+            swagger_ui
+                .url("/api-docs/openapi.json", doc)
+                .config(Config::new([format!("{}/api-docs/openapi.json", prefix)]))
+        }
+        None => swagger_ui.url("/api-docs/openapi.json", doc),
+    }
 }
@@ -0,0 +1,263 @@
+//! # API密钥认证
+//!
+//! 设置[`RouterOptions::auth`](super::RouterOptions::auth)后，API路由只接受带有有效API密钥的请求：
+//!
+//! - 密钥从请求头（默认`X-API-Key`）或查询参数（默认`api_key`）中读取，两者都有时使用请求头
+//! - 缺少密钥或密钥无效时返回`401 Unauthorized`
+//! - 超过该密钥的每秒请求数或每日配额时返回`429 Too Many Requests`并附带`Retry-After`，每日配额在UTC零点重置
+//! - Swagger UI、OpenAPI文档、运维接口与指标不需要密钥
+//!
+//! 查询参数中的密钥由[`take_query_key`]在记录访问日志之前从URI中移除，不会出现在日志与响应缓存的键中。
+//! 请求span的`api_key`字段为密钥名称。
+//!
+//! 调用方可以通过`GET /usage`查看自己的用量（该请求不计入用量，也不受限制），所有密钥的用量见[`ApiKeys::usage`]。
+
+use crate::client::limiter::RateLimiter;
+use crate::config::AuthConfig;
+use crate::dto::UsageData;
+use crate::error::AppError;
+use axum::{
+    extract::{Request, State},
+    http::{HeaderName, Uri, uri::PathAndQuery},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{NaiveDate, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 不需要API密钥的路径前缀
+const PUBLIC_PATHS: [&str; 2] = ["/swagger-ui", "/api-docs"];
+
+/// 查看用量的路径，需要API密钥，但不计入用量
+const USAGE_PATH: &str = "/usage";
+
+/// 所有API密钥及其用量
+///
+/// 克隆后的实例共享同一份用量。
+#[derive(Clone)]
+pub struct ApiKeys {
+    inner: Arc<ApiKeysInner>,
+}
+
+struct ApiKeysInner {
+    header: HeaderName,
+    query_param: Option<String>,
+    keys: HashMap<String, Arc<KeyState>>,
+}
+
+/// 一个API密钥的限制与用量
+struct KeyState {
+    name: String,
+    limiter: Option<RateLimiter>,
+    requests_per_second: Option<f64>,
+    daily_quota: Option<u64>,
+    counters: Mutex<Counters>,
+}
+
+struct Counters {
+    total: u64,
+    today: u64,
+    day: NaiveDate,
+    rejected: u64,
+}
+
+/// 当前请求使用的API密钥，由[`authenticate`]写入请求扩展
+#[derive(Clone)]
+pub struct Caller(Arc<KeyState>);
+
+/// 从查询参数中取出的API密钥
+#[derive(Clone)]
+struct QueryKey(String);
+
+impl std::fmt::Debug for ApiKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<_> = self.inner.keys.values().map(|key| key.name.as_str()).collect();
+        names.sort_unstable();
+        f.debug_struct("ApiKeys")
+            .field("header", &self.inner.header)
+            .field("query_param", &self.inner.query_param)
+            .field("keys", &names)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for Caller {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Caller").field(&self.0.name).finish()
+    }
+}
+
+impl ApiKeys {
+    /// 按配置创建，用量从0开始计数
+    pub fn new(config: &AuthConfig) -> Self {
+        let today = Utc::now().date_naive();
+        let keys = config
+            .keys
+            .iter()
+            .map(|key| {
+                let state = KeyState {
+                    name: key.name.clone(),
+                    limiter: key.rate_limit.clone().map(RateLimiter::new),
+                    requests_per_second: key.rate_limit.as_ref().map(|r| r.requests_per_second),
+                    daily_quota: key.daily_quota,
+                    counters: Mutex::new(Counters {
+                        total: 0,
+                        today: 0,
+                        day: today,
+                        rejected: 0,
+                    }),
+                };
+                (key.key.clone(), Arc::new(state))
+            })
+            .collect();
+        Self {
+            inner: Arc::new(ApiKeysInner {
+                header: HeaderName::from_bytes(config.header.as_bytes()).expect("配置中的请求头名称已校验"),
+                query_param: config.query_param.clone(),
+                keys,
+            }),
+        }
+    }
+
+    /// 所有API密钥的用量，按名称排序
+    pub fn usage(&self) -> Vec<UsageData> {
+        let mut usage: Vec<_> = self.inner.keys.values().map(|key| key.usage()).collect();
+        usage.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        usage
+    }
+}
+
+impl Caller {
+    /// 密钥名称
+    pub fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// 该密钥的用量
+    pub fn usage(&self) -> UsageData {
+        self.0.usage()
+    }
+}
+
+impl KeyState {
+    /// 检查速率限制与每日配额，通过时计入用量
+    ///
+    /// 拒绝时返回拒绝原因（`quota_exceeded`或`rate_limited`）与建议的重试等待时间。
+    fn admit(&self) -> Result<(), (&'static str, Duration)> {
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Utc::now();
+        if counters.day != now.date_naive() {
+            counters.day = now.date_naive();
+            counters.today = 0;
+        }
+        if let Some(quota) = self.daily_quota
+            && counters.today >= quota
+        {
+            counters.rejected += 1;
+            let midnight = now.date_naive().succ_opt().and_then(|d| d.and_hms_opt(0, 0, 0));
+            let retry_after = midnight
+                .and_then(|midnight| (midnight.and_utc() - now).to_std().ok())
+                .unwrap_or(Duration::from_secs(1));
+            return Err(("quota_exceeded", retry_after));
+        }
+        if let Some(retry_after) = self.limiter.as_ref().and_then(RateLimiter::try_take) {
+            counters.rejected += 1;
+            return Err(("rate_limited", retry_after));
+        }
+        counters.total += 1;
+        counters.today += 1;
+        Ok(())
+    }
+
+    fn usage(&self) -> UsageData {
+        let counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        let today = if counters.day == Utc::now().date_naive() {
+            counters.today
+        } else {
+            0
+        };
+        UsageData {
+            name: self.name.clone(),
+            total_requests: counters.total,
+            today_requests: today,
+            rejected_requests: counters.rejected,
+            requests_per_second: self.requests_per_second,
+            daily_quota: self.daily_quota,
+            remaining_today: self.daily_quota.map(|quota| quota.saturating_sub(today)),
+        }
+    }
+}
+
+/// 将查询参数中的API密钥从URI中移除，交由[`authenticate`]校验
+///
+/// 应位于[`trace_request`](super::middleware::trace_request)之外，使密钥不出现在访问日志中。
+pub async fn take_query_key(State(keys): State<ApiKeys>, mut request: Request, next: Next) -> Response {
+    if let Some(param) = &keys.inner.query_param
+        && let Some(query) = request.uri().query()
+    {
+        let mut key = None;
+        let rest: Vec<&str> = query
+            .split('&')
+            .filter(|pair| match url::form_urlencoded::parse(pair.as_bytes()).next() {
+                Some((name, value)) if name == param.as_str() => {
+                    key.get_or_insert_with(|| value.into_owned());
+                    false
+                }
+                _ => true,
+            })
+            .collect();
+        if let Some(key) = key {
+            let path = request.uri().path();
+            let path_and_query = match rest.join("&") {
+                rest if rest.is_empty() => path.to_string(),
+                rest => format!("{}?{}", path, rest),
+            };
+            let mut parts = request.uri().clone().into_parts();
+            parts.path_and_query = PathAndQuery::try_from(path_and_query).ok();
+            if parts.path_and_query.is_some()
+                && let Ok(uri) = Uri::from_parts(parts)
+            {
+                *request.uri_mut() = uri;
+            }
+            request.extensions_mut().insert(QueryKey(key));
+        }
+    }
+    next.run(request).await
+}
+
+/// 校验请求中的API密钥，并检查该密钥的速率限制与每日配额
+///
+/// 通过后将[`Caller`]写入请求扩展。Swagger UI与OpenAPI文档不需要密钥。
+pub async fn authenticate(State(keys): State<ApiKeys>, mut request: Request, next: Next) -> Result<Response, AppError> {
+    let path = request.uri().path();
+    if PUBLIC_PATHS.iter().any(|prefix| path.starts_with(prefix)) {
+        return Ok(next.run(request).await);
+    }
+
+    let presented = match request.headers().get(&keys.inner.header) {
+        Some(value) => value.to_str().ok().map(str::to_string),
+        None => request.extensions().get::<QueryKey>().map(|key| key.0.clone()),
+    };
+    let Some(state) = presented.and_then(|key| keys.inner.keys.get(key.trim()).cloned()) else {
+        #[cfg(feature = "metrics")]
+        crate::metrics::api_key_request("", "unauthorized");
+        return Err(AppError::Unauthorized);
+    };
+    tracing::Span::current().record("api_key", state.name.as_str());
+    if request.uri().path() == USAGE_PATH {
+        request.extensions_mut().insert(Caller(state));
+        return Ok(next.run(request).await);
+    }
+
+    let admitted = state.admit();
+    #[cfg(feature = "metrics")]
+    crate::metrics::api_key_request(&state.name, admitted.as_ref().map_or_else(|(result, _)| *result, |()| "ok"));
+    if let Err((_, retry_after)) = admitted {
+        return Err(AppError::QuotaExceeded { retry_after });
+    }
+
+    request.extensions_mut().insert(Caller(state));
+    Ok(next.run(request).await)
+}
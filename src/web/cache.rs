@@ -0,0 +1,398 @@
+//! # 响应缓存
+//!
+//! 为GET请求的成功响应添加HTTP缓存响应头，并在服务端缓存响应：
+//!
+//! - 根据响应体计算强`ETag`，请求的`If-None-Match`匹配时返回`304 Not Modified`
+//! - `Last-Modified`为响应生成的时间，没有`If-None-Match`时支持`If-Modified-Since`
+//! - 按[`CacheConfig`]中的路由规则设置`Cache-Control`，不缓存的路由设置为`no-store`
+//! - 在服务端缓存响应：新鲜期内直接返回缓存；过期后在`stale-while-revalidate`时长内
+//!   先返回旧响应，同时在后台刷新
+//!
+//! 命中服务端缓存的响应带有`Age`响应头。请求头带有`Cache-Control: no-cache`时跳过服务端缓存。
+//!
+//! 通过[`ResponseCache::live`]创建时，每个请求都使用[`LiveConfig`]中当前的缓存配置；
+//! 已缓存的响应仍按缓存时的策略过期。
+
+use crate::config::reload::LiveConfig;
+use crate::config::{CacheConfig, CachePolicy};
+use crate::error::AppError;
+use crate::tasks::Tasks;
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// 可缓存的响应体大小上限，更大的响应不计算`ETag`也不缓存
+const MAX_CACHED_BODY: usize = 8 * 1024 * 1024;
+
+/// 304响应中保留的响应头
+const NOT_MODIFIED_HEADERS: [header::HeaderName; 5] = [
+    header::ETAG,
+    header::CACHE_CONTROL,
+    header::LAST_MODIFIED,
+    header::AGE,
+    header::VARY,
+];
+
+/// 服务端缓存的一个响应
+struct Entry {
+    headers: HeaderMap,
+    body: Bytes,
+    stored_at: Instant,
+    policy: CachePolicy,
+    revalidating: bool,
+}
+
+impl Entry {
+    /// 超过新鲜期与`stale-while-revalidate`时长后不再使用
+    fn expired(&self) -> bool {
+        self.stored_at.elapsed() >= self.policy.max_age + self.policy.stale_while_revalidate
+    }
+
+    fn response(&self) -> Response {
+        let mut response = Response::new(Body::from(self.body.clone()));
+        *response.headers_mut() = self.headers.clone();
+        response
+            .headers_mut()
+            .insert(header::AGE, HeaderValue::from(self.stored_at.elapsed().as_secs()));
+        response
+    }
+}
+
+/// 查找服务端缓存的结果
+enum Lookup {
+    /// 新鲜期内的缓存
+    Fresh(Response),
+
+    /// 已过期但仍可使用的缓存，`revalidate`为`true`时需要由本次请求发起后台刷新
+    Stale { response: Response, revalidate: bool },
+
+    /// 没有可用的缓存
+    Miss,
+
+    /// 请求要求跳过缓存
+    Bypass,
+}
+
+impl Lookup {
+    /// 指标中的查找结果标签
+    #[cfg(feature = "metrics")]
+    fn label(&self) -> &'static str {
+        match self {
+            Lookup::Fresh(_) => "hit",
+            Lookup::Stale { .. } => "stale",
+            Lookup::Miss => "miss",
+            Lookup::Bypass => "bypass",
+        }
+    }
+}
+
+/// 缓存配置的来源
+#[derive(Clone)]
+enum Source {
+    /// 固定的配置
+    Fixed(Arc<CacheConfig>),
+
+    /// 可重新加载的配置，其中的缓存配置为`None`时不缓存
+    Live(LiveConfig),
+}
+
+/// 响应缓存
+///
+/// 克隆后的实例共享同一份服务端缓存。后台刷新在[`with_tasks`](Self::with_tasks)指定的任务组中进行。
+#[derive(Clone)]
+pub struct ResponseCache {
+    config: Source,
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+    tasks: Tasks,
+}
+
+impl ResponseCache {
+    /// 创建响应缓存
+    pub fn new(config: CacheConfig) -> Self {
+        Self::with_source(Source::Fixed(Arc::new(config)))
+    }
+
+    /// 创建使用`config`中当前缓存配置的响应缓存，配置重新加载后立即生效
+    pub fn live(config: LiveConfig) -> Self {
+        Self::with_source(Source::Live(config))
+    }
+
+    fn with_source(config: Source) -> Self {
+        Self {
+            config,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            tasks: Tasks::default(),
+        }
+    }
+
+    /// 指定后台刷新任务所属的任务组
+    pub fn with_tasks(mut self, tasks: Tasks) -> Self {
+        self.tasks = tasks;
+        self
+    }
+
+    /// 使用当前的缓存配置调用`f`，未启用缓存时为`None`
+    fn with_config<R>(&self, f: impl FnOnce(Option<&CacheConfig>) -> R) -> R {
+        match &self.config {
+            Source::Fixed(config) => f(Some(config)),
+            Source::Live(live) => f(live.load().cache.as_ref()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lookup(&self, key: &str) -> Lookup {
+        let mut entries = self.lock();
+        let Some(entry) = entries.get_mut(key) else {
+            return Lookup::Miss;
+        };
+        if entry.stored_at.elapsed() < entry.policy.max_age {
+            return Lookup::Fresh(entry.response());
+        }
+        if entry.expired() {
+            entries.remove(key);
+            #[cfg(feature = "metrics")]
+            crate::metrics::cache_entries(entries.len());
+            return Lookup::Miss;
+        }
+        let revalidate = !entry.revalidating;
+        entry.revalidating = true;
+        Lookup::Stale {
+            response: entry.response(),
+            revalidate,
+        }
+    }
+
+    fn store(&self, key: String, headers: HeaderMap, body: Bytes, policy: CachePolicy) {
+        let max_entries = self.with_config(|config| config.map_or(0, |config| config.max_entries));
+        let mut entries = self.lock();
+        if entries.len() >= max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, entry| !entry.expired());
+            if entries.len() >= max_entries
+                && let Some(oldest) = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.stored_at)
+                    .map(|(key, _)| key.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            key,
+            Entry {
+                headers,
+                body,
+                stored_at: Instant::now(),
+                policy,
+                revalidating: false,
+            },
+        );
+        #[cfg(feature = "metrics")]
+        crate::metrics::cache_entries(entries.len());
+    }
+
+    /// 后台刷新失败时，允许之后的请求再次发起刷新
+    fn revalidate_failed(&self, key: &str) {
+        if let Some(entry) = self.lock().get_mut(key) {
+            entry.revalidating = false;
+        }
+    }
+}
+
+/// 为响应添加HTTP缓存响应头，处理条件请求，并在服务端缓存响应
+pub async fn cache_responses(State(cache): State<ResponseCache>, request: Request, next: Next) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+    let Some((policy, server_cache)) = cache.with_config(|config| {
+        config.map(|config| (config.policy_for(request.uri().path()), config.max_entries > 0))
+    }) else {
+        return next.run(request).await;
+    };
+    let conditions = request.headers().clone();
+    let Some(policy) = policy else {
+        let mut response = next.run(request).await;
+        if response.status() == StatusCode::OK && !response.headers().contains_key(header::CACHE_CONTROL) {
+            response
+                .headers_mut()
+                .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        }
+        return response;
+    };
+
+    let key = request.uri().to_string();
+    if server_cache {
+        let lookup = if no_cache(request.headers()) {
+            Lookup::Bypass
+        } else {
+            cache.lookup(&key)
+        };
+        #[cfg(feature = "metrics")]
+        crate::metrics::cache_lookup(lookup.label());
+        match lookup {
+            Lookup::Fresh(response) => return conditional(&conditions, response),
+            Lookup::Stale { response, revalidate } => {
+                if revalidate {
+                    spawn_revalidate(cache, key, &request, policy, next);
+                }
+                return conditional(&conditions, response);
+            }
+            Lookup::Miss | Lookup::Bypass => {}
+        }
+    }
+
+    let (response, storable) = match finalize(next.run(request).await, policy).await {
+        Ok(finalized) => finalized,
+        Err(response) => return response,
+    };
+    let (parts, body) = response.into_parts();
+    if server_cache && storable {
+        cache.store(key, parts.headers.clone(), body.clone(), policy);
+    }
+    conditional(&conditions, Response::from_parts(parts, Body::from(body)))
+}
+
+/// 在后台重新生成响应并更新缓存
+///
+/// 刷新请求复制原请求的URI、请求头与扩展（路径参数、`MatchedPath`等路由信息），去掉条件请求头。
+fn spawn_revalidate(cache: ResponseCache, key: String, original: &Request, policy: CachePolicy, next: Next) {
+    let mut request = Request::new(Body::empty());
+    *request.uri_mut() = original.uri().clone();
+    *request.version_mut() = original.version();
+    *request.headers_mut() = original.headers().clone();
+    *request.extensions_mut() = original.extensions().clone();
+    request.headers_mut().remove(header::IF_NONE_MATCH);
+    request.headers_mut().remove(header::IF_MODIFIED_SINCE);
+    let tasks = cache.tasks.clone();
+    tasks.spawn(async move {
+        match finalize(next.run(request).await, policy).await {
+            Ok((response, true)) => {
+                let (parts, body) = response.into_parts();
+                cache.store(key, parts.headers, body, policy);
+            }
+            _ => {
+                tracing::warn!("后台刷新缓存的响应失败: {}", key);
+                cache.revalidate_failed(&key);
+            }
+        }
+    });
+}
+
+/// 读取成功响应的响应体并补充缓存响应头
+///
+/// 返回的布尔值表示响应能否在服务端缓存。非`200`或过大的响应原样通过`Err`返回。
+async fn finalize(response: Response, policy: CachePolicy) -> Result<(Response<Bytes>, bool), Response> {
+    use axum::body::HttpBody;
+
+    if response.status() != StatusCode::OK
+        || response
+            .body()
+            .size_hint()
+            .upper()
+            .is_none_or(|size| size > MAX_CACHED_BODY as u64)
+    {
+        return Err(response);
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_CACHED_BODY).await {
+        Ok(body) => body,
+        Err(e) => return Err(AppError::Internal(format!("读取响应体失败: {}", e)).into_response()),
+    };
+
+    let headers = &mut parts.headers;
+    if !headers.contains_key(header::ETAG)
+        && let Ok(etag) = HeaderValue::from_str(&etag(&body))
+    {
+        headers.insert(header::ETAG, etag);
+    }
+    if !headers.contains_key(header::LAST_MODIFIED)
+        && let Ok(now) = HeaderValue::from_str(&http_date(chrono::Utc::now()))
+    {
+        headers.insert(header::LAST_MODIFIED, now);
+    }
+    let storable = !headers.contains_key(header::CACHE_CONTROL) && !headers.contains_key(header::SET_COOKIE);
+    if !headers.contains_key(header::CACHE_CONTROL)
+        && let Ok(cache_control) = HeaderValue::from_str(&policy.cache_control())
+    {
+        headers.insert(header::CACHE_CONTROL, cache_control);
+    }
+    Ok((Response::from_parts(parts, body), storable))
+}
+
+/// 条件请求匹配时返回`304 Not Modified`，否则返回原响应
+fn conditional(request: &HeaderMap, response: Response) -> Response {
+    if !not_modified(request, response.headers()) {
+        return response;
+    }
+    let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+    for name in NOT_MODIFIED_HEADERS {
+        if let Some(value) = response.headers().get(&name) {
+            not_modified.headers_mut().insert(name, value.clone());
+        }
+    }
+    not_modified
+}
+
+/// 判断客户端缓存的响应是否仍然有效
+///
+/// 有`If-None-Match`时按`ETag`弱比较，忽略`If-Modified-Since`。
+fn not_modified(request: &HeaderMap, response: &HeaderMap) -> bool {
+    if let Some(if_none_match) = request.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+        let Some(etag) = response.get(header::ETAG).and_then(|v| v.to_str().ok()) else {
+            return false;
+        };
+        let etag = etag.trim_start_matches("W/");
+        return if_none_match
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag);
+    }
+
+    let parse = |headers: &HeaderMap, name| {
+        headers
+            .get(name)
+            .and_then(|v: &HeaderValue| v.to_str().ok())
+            .and_then(|v| chrono::DateTime::parse_from_rfc2822(v).ok())
+    };
+    match (
+        parse(request, header::IF_MODIFIED_SINCE),
+        parse(response, header::LAST_MODIFIED),
+    ) {
+        (Some(since), Some(modified)) => modified <= since,
+        _ => false,
+    }
+}
+
+/// 请求是否要求跳过缓存
+fn no_cache(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|directive| matches!(directive.trim(), "no-cache" | "no-store"))
+}
+
+/// 根据响应体计算强`ETag`（FNV-1a 64位哈希与长度）
+fn etag(body: &[u8]) -> String {
+    let hash = body.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("\"{:016x}-{:x}\"", hash, body.len())
+}
+
+/// HTTP日期格式，如`Sun, 06 Nov 1994 08:49:37 GMT`
+fn http_date(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
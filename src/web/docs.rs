@@ -4,10 +4,24 @@
 //!
 //! 使用`utoipa`库生成OpenAPI 3.0规范文档，支持Swagger UI集成。
 //! 仅在启用`swagger-ui` feature时才会被使用。
+//!
+//! 文档中包含API密钥认证方式`api_key`（请求头`X-API-Key`）与`api_key_query`（查询参数`api_key`），
+//! 运维接口不需要密钥。
 
+use crate::client::breaker::{CircuitState, CircuitStatus};
+use crate::client::coalesce::CoalesceStats;
+use crate::client::upstream::UpstreamStatus;
+use crate::config::{AuthConfig, ConfigSummary};
 use crate::dto::*;
 use crate::web::handler::*;
-use utoipa::OpenApi;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+/// 通过请求头携带API密钥的认证方式名称
+const HEADER_SCHEME: &str = "api_key";
+
+/// 通过查询参数携带API密钥的认证方式名称
+const QUERY_SCHEME: &str = "api_key_query";
 
 /// API文档结构体
 ///
@@ -18,15 +32,35 @@ use utoipa::OpenApi;
     paths(
         get_song,
         get_all_songs,
+        get_songs_batch,
         get_album,
         get_album_detail,
+        get_album_full,
         get_all_albums,
+        get_albums_batch,
         get_all_news,
         get_news_detail,
         get_font,
+        health,
         search,
         search_albums,
         search_news,
+        search_songs,
+        search_all,
+        list_artists,
+        get_artist,
+        news_rss,
+        news_atom,
+        album_podcast,
+        artist_podcast,
+        get_song_audio,
+        album_m3u8,
+        album_xspf,
+        album_pls,
+        healthz,
+        readyz,
+        version,
+        usage,
     ),
     components(schemas(
         // 响应类型
@@ -34,11 +68,23 @@ use utoipa::OpenApi;
         AllSongsResp,
         AlbumResp,
         AlbumDetailResp,
+        AlbumFullResp,
+        SongBatchResp,
+        AlbumBatchResp,
         SearchResp,
         SearchAlbumResp,
         SearchNewsResp,
         NewsDetailResp,
         FontResp,
+        SongSearchResp,
+        ExtendedSearchResp,
+        ArtistListResp,
+        ArtistResp,
+        HealthResp,
+        LivenessResp,
+        ReadinessResp,
+        VersionResp,
+        UsageResp,
         // 数据模型
         SongData,
         AllSongsData,
@@ -46,6 +92,8 @@ use utoipa::OpenApi;
         AlbumData,
         AlbumDetailData,
         AlbumDetailSongItem,
+        AlbumFullData,
+        AlbumFullTrack,
         AllAlbumsItem,
         SearchData,
         SearchAlbumData,
@@ -55,17 +103,92 @@ use utoipa::OpenApi;
         NewsDetailData,
         FontData,
         FontItem,
+        SongSearchData,
+        SongSearchItem,
+        ExtendedSearchData,
+        ArtistSummary,
+        ArtistDetail,
+        HealthData,
+        CircuitStatus,
+        CircuitState,
+        CoalesceStats,
+        UpstreamStatus,
+        LivenessData,
+        ReadinessData,
+        VersionData,
+        ConfigSummary,
+        UsageData,
     )),
+    modifiers(&SecurityAddon),
+    security(("api_key" = []), ("api_key_query" = [])),
     tags(
         (name = "search", description = "搜索相关接口"),
         (name = "songs", description = "歌曲相关接口"),
         (name = "albums", description = "专辑相关接口"),
         (name = "news", description = "新闻(动向)相关接口"),
-        (name = "others", description = "其他接口")
+        (name = "artists", description = "艺术家相关接口"),
+        (name = "feeds", description = "订阅源相关接口"),
+        (name = "others", description = "其他接口"),
+        (name = "ops", description = "运维接口（存活检查、就绪检查与版本信息），需在路由选项中启用")
     )
 )]
 struct ApiDoc;
 
+/// 添加API密钥认证方式
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(HEADER_SCHEME, header_scheme("X-API-Key"));
+        components.add_security_scheme(QUERY_SCHEME, query_scheme("api_key"));
+    }
+}
+
+fn header_scheme(header: &str) -> SecurityScheme {
+    SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+        header,
+        "API密钥，在请求头中携带；启用API密钥认证时需要",
+    )))
+}
+
+fn query_scheme(param: &str) -> SecurityScheme {
+    SecurityScheme::ApiKey(ApiKey::Query(ApiKeyValue::with_description(
+        param,
+        "API密钥，在查询参数中携带；启用API密钥认证时需要",
+    )))
+}
+
+/// 按实际的认证配置调整文档中的API密钥认证方式
+///
+/// `auth`为`None`时移除认证方式与`/usage`；未启用查询参数时移除`api_key_query`。
+pub(crate) fn apply_auth(doc: &mut utoipa::openapi::OpenApi, auth: Option<&AuthConfig>) {
+    let Some(components) = doc.components.as_mut() else {
+        return;
+    };
+    match auth {
+        Some(auth) => {
+            components.add_security_scheme(HEADER_SCHEME, header_scheme(&auth.header));
+            match &auth.query_param {
+                Some(param) => components.add_security_scheme(QUERY_SCHEME, query_scheme(param)),
+                None => {
+                    components.security_schemes.remove(QUERY_SCHEME);
+                    doc.security = Some(vec![utoipa::openapi::SecurityRequirement::new(
+                        HEADER_SCHEME,
+                        Vec::<String>::new(),
+                    )]);
+                }
+            }
+        }
+        None => {
+            components.security_schemes.remove(HEADER_SCHEME);
+            components.security_schemes.remove(QUERY_SCHEME);
+            doc.security = None;
+            doc.paths.paths.remove("/usage");
+        }
+    }
+}
+
 /// 获取OpenAPI文档实例
 ///
 /// 生成并返回配置好的OpenAPI文档实例，用于Swagger UI展示。
@@ -5,13 +5,29 @@
 //! 每个处理函数都对应一个特定的API端点，负责接收请求、调用远程API并返回响应。
 //! 所有处理函数都使用`utoipa`进行OpenAPI文档注解。
 
+use crate::client::meta::WithMeta;
 use crate::client::remote::RemoteApiClient;
 use crate::error::AppError;
 use crate::dto::*;
+use crate::feed::news::{self, NewsFeedOptions};
+use crate::feed::podcast;
+use crate::playlist::{self, Playlist, UrlMode};
+use crate::web::auth::Caller;
+use crate::web::state::{AppState, ProbeState};
 use axum::{
-    Json,
-    extract::{Path, Query, State},
+    Extension, Json,
+    extract::{OriginalUri, Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header, uri::Authority},
+    response::{IntoResponse, Redirect, Response},
 };
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 订阅源单次最多拉取的新闻页数
+const MAX_FEED_PAGES: usize = 10;
+
+/// 批量查询单次最多包含的cid数量（去重后）
+const MAX_BATCH_SIZE: usize = 100;
 
 /// 获取歌曲详情
 /// 
@@ -29,9 +45,10 @@ use axum::{
 )]
 pub async fn get_song(
     Path(cid): Path<String>,
-    State(client): State<RemoteApiClient>,
-) -> Result<Json<SongResp>, AppError> {
-    client.get_song(cid).await.map(Json)
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let resp = state.client.get_song_with_meta(cid).await?;
+    Ok(proxied(&state, resp))
 }
 
 /// 获取所有歌曲列表
@@ -46,9 +63,36 @@ pub async fn get_song(
     tag = "songs"
 )]
 pub async fn get_all_songs(
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let resp = state.client.get_all_songs_with_meta().await?;
+    Ok(proxied(&state, resp))
+}
+
+/// 批量获取歌曲详情
+/// 
+/// 请求体为歌曲cid列表，返回以cid为键的查询结果。重复的cid只会请求一次，
+/// 单首歌曲获取失败时在对应条目的`error`字段中报告。
+#[utoipa::path(
+    post,
+    path="/songs/batch",
+    request_body(
+        content = Vec<String>,
+        description = "歌曲cid列表，去重后最多100个",
+        example = json!(["953953", "514512"])
+    ),
+    responses(
+        (status=200,description="以cid为键的歌曲详情",body=ApiResp<BTreeMap<String, BatchEntry<SongData>>>),
+        (status=400,description="cid数量超过上限")
+    ),
+    tag = "songs"
+)]
+pub async fn get_songs_batch(
     State(client): State<RemoteApiClient>,
-) -> Result<Json<AllSongsResp>, AppError> {
-    client.get_all_songs().await.map(Json)
+    Json(ids): Json<Vec<String>>,
+) -> Result<Json<SongBatchResp>, AppError> {
+    check_batch_size(&ids)?;
+    client.get_songs_batch(ids).await.map(Json)
 }
 
 /// 获取专辑信息
@@ -67,9 +111,10 @@ pub async fn get_all_songs(
 )]
 pub async fn get_album(
     Path(cid): Path<String>,
-    State(client): State<RemoteApiClient>,
-) -> Result<Json<AlbumResp>, AppError> {
-    client.get_album(cid).await.map(Json)
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let resp = state.client.get_album_with_meta(cid).await?;
+    Ok(proxied(&state, resp))
 }
 
 /// 获取专辑详情
@@ -87,10 +132,33 @@ pub async fn get_album(
     tag = "albums"
 )]
 pub async fn get_album_detail(
+    Path(cid): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let resp = state.client.get_album_detail_with_meta(cid).await?;
+    Ok(proxied(&state, resp))
+}
+
+/// 获取完整专辑数据
+/// 
+/// 根据专辑cid获取专辑信息及每首歌曲的完整详情（包含音频与歌词URL），
+/// 避免客户端逐首请求`/song/{cid}`。单首歌曲获取失败时在对应曲目中报告错误。
+#[utoipa::path(
+    get,
+    path="/album/{cid}/full",
+    params(
+        ("cid"=String,Path,description="专辑cid")
+    ),
+    responses(
+        (status=200,description="完整专辑数据",body=ApiResp<AlbumFullData>)
+    ),
+    tag = "albums"
+)]
+pub async fn get_album_full(
     Path(cid): Path<String>,
     State(client): State<RemoteApiClient>,
-) -> Result<Json<AlbumDetailResp>, AppError> {
-    client.get_album_detail(cid).await.map(Json)
+) -> Result<Json<AlbumFullResp>, AppError> {
+    client.get_album_full(cid).await.map(Json)
 }
 
 /// 获取所有专辑列表
@@ -105,9 +173,49 @@ pub async fn get_album_detail(
     tag = "albums"
 )]
 pub async fn get_all_albums(
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let resp = state.client.get_all_albums_with_meta().await?;
+    Ok(proxied(&state, resp))
+}
+
+/// 批量获取专辑信息
+/// 
+/// 请求体为专辑cid列表，返回以cid为键的查询结果。重复的cid只会请求一次，
+/// 单个专辑获取失败时在对应条目的`error`字段中报告。
+#[utoipa::path(
+    post,
+    path="/albums/batch",
+    request_body(
+        content = Vec<String>,
+        description = "专辑cid列表，去重后最多100个",
+        example = json!(["3888", "7770"])
+    ),
+    responses(
+        (status=200,description="以cid为键的专辑信息",body=ApiResp<BTreeMap<String, BatchEntry<AlbumData>>>),
+        (status=400,description="cid数量超过上限")
+    ),
+    tag = "albums"
+)]
+pub async fn get_albums_batch(
     State(client): State<RemoteApiClient>,
-) -> Result<Json<ApiResp<Vec<AllAlbumsItem>>>, AppError> {
-    client.get_all_albums().await.map(Json)
+    Json(ids): Json<Vec<String>>,
+) -> Result<Json<AlbumBatchResp>, AppError> {
+    check_batch_size(&ids)?;
+    client.get_albums_batch(ids).await.map(Json)
+}
+
+/// 检查批量查询去重后的cid数量是否超过上限
+fn check_batch_size(ids: &[String]) -> Result<(), AppError> {
+    let distinct: BTreeSet<&str> = ids.iter().map(String::as_str).collect();
+    if distinct.len() > MAX_BATCH_SIZE {
+        return Err(AppError::BadRequest(format!(
+            "单次最多查询{}个cid，实际为{}个",
+            MAX_BATCH_SIZE,
+            distinct.len()
+        )));
+    }
+    Ok(())
 }
 
 /// 综合搜索
@@ -126,9 +234,10 @@ pub async fn get_all_albums(
 )]
 pub async fn search(
     Query(q): Query<SearchQuery>,
-    State(client): State<RemoteApiClient>,
-) -> Result<Json<SearchResp>, AppError> {
-    client.search(q.keyword).await.map(Json)
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let resp = state.client.search_with_meta(q.keyword).await?;
+    Ok(proxied(&state, resp))
 }
 
 /// 搜索专辑
@@ -148,9 +257,10 @@ pub async fn search(
 )]
 pub async fn search_albums(
     Query(q): Query<SearchAlbumQuery>,
-    State(client): State<RemoteApiClient>,
-) -> Result<Json<SearchAlbumResp>, AppError> {
-    client.search_albums(q.keyword, q.last_cid).await.map(Json)
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let resp = state.client.search_albums_with_meta(q.keyword, q.last_cid).await?;
+    Ok(proxied(&state, resp))
 }
 
 /// 搜索新闻
@@ -170,9 +280,71 @@ pub async fn search_albums(
 )]
 pub async fn search_news(
     Query(q): Query<NewsQuery>,
-    State(client): State<RemoteApiClient>,
-) -> Result<Json<SearchNewsResp>, AppError> {
-    client.search_news(q.keyword, q.last_cid).await.map(Json)
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let resp = state.client.search_news_with_meta(q.keyword, q.last_cid).await?;
+    Ok(proxied(&state, resp))
+}
+
+/// 搜索歌曲
+/// 
+/// 在本地搜索索引中按歌曲名、艺术家和专辑名筛选歌曲，支持模糊匹配与拼音/首字母匹配，至少需要提供一个查询参数。
+/// 索引超过10分钟后，下一次请求仍使用旧索引，同时在后台重建。
+#[utoipa::path(
+    get,
+    path="/search/song",
+    params(SongSearchQuery),
+    responses(
+        (status=200,description="歌曲搜索结果",body=ApiResp<SongSearchData>),
+        (status=400,description="没有提供任何查询参数")
+    ),
+    tags=["search","songs"],
+)]
+pub async fn search_songs(
+    Query(q): Query<SongSearchQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<SongSearchResp>, AppError> {
+    let blank = |v: &Option<String>| v.as_deref().is_none_or(|v| v.trim().is_empty());
+    if blank(&q.keyword) && blank(&q.artist) && blank(&q.album) {
+        return Err(AppError::BadRequest("keyword、artist、album至少需要提供一个".into()));
+    }
+    let index = state.index.get().await?;
+    Ok(Json(ApiResp::success(SongSearchData {
+        list: index.search.search_songs(&q),
+    })))
+}
+
+/// 综合搜索（包含歌曲）
+/// 
+/// 在上游综合搜索（专辑和新闻）的基础上，增加本地歌曲搜索结果。
+#[utoipa::path(
+    get,
+    path="/search/all",
+    params(
+        ("keyword" = String, Query, description = "搜索关键词")
+    ),
+    responses(
+        (status=200,description="搜索结果",body=ApiResp<ExtendedSearchData>)
+    ),
+    tag = "search"
+)]
+pub async fn search_all(
+    Query(q): Query<SearchQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ExtendedSearchResp>, AppError> {
+    let (upstream, index) = futures::try_join!(
+        state.client.search(q.keyword.clone()),
+        state.index.get(),
+    )?;
+    let songs = index.search.search_songs(&SongSearchQuery {
+        keyword: Some(q.keyword),
+        ..Default::default()
+    });
+    Ok(Json(ApiResp::success(ExtendedSearchData {
+        albums: upstream.data.albums,
+        news: upstream.data.news,
+        songs: SongSearchData { list: songs },
+    })))
 }
 
 /// 获取所有新闻列表
@@ -191,9 +363,10 @@ pub async fn search_news(
 )]
 pub async fn get_all_news(
     Query(q): Query<AllNewsQuery>,
-    State(client): State<RemoteApiClient>,
-) -> Result<Json<SearchNewsResp>, AppError> {
-    client.get_all_news(q.last_cid).await.map(Json)
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let resp = state.client.get_all_news_with_meta(q.last_cid).await?;
+    Ok(proxied(&state, resp))
 }
 
 /// 获取新闻详情
@@ -212,9 +385,10 @@ pub async fn get_all_news(
 )]
 pub async fn get_news_detail(
     Path(cid): Path<String>,
-    State(client): State<RemoteApiClient>,
-) -> Result<Json<NewsDetailResp>, AppError> {   
-    client.get_news_detail(cid).await.map(Json)
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let resp = state.client.get_news_detail_with_meta(cid).await?;
+    Ok(proxied(&state, resp))
 }
 
 /// 获取字体配置
@@ -229,7 +403,537 @@ pub async fn get_news_detail(
     tag = "others"
 )]
 pub async fn get_font(
-    State(client): State<RemoteApiClient>
-) -> Result<Json<FontResp>, AppError> {
-    client.get_font().await.map(Json)
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let resp = state.client.get_font_with_meta().await?;
+    Ok(proxied(&state, resp))
+}
+
+/// 获取服务健康状态
+/// 
+/// 返回各上游状态、熔断器状态与请求合并统计，不会访问上游。
+/// 熔断打开、半开或存在不健康的上游时整体状态为`degraded`。
+#[utoipa::path(
+    get,
+    path="/health",
+    responses(
+        (status=200,description="服务健康状态",body=ApiResp<HealthData>)
+    ),
+    tag = "others"
+)]
+pub async fn health(State(client): State<RemoteApiClient>) -> Json<HealthResp> {
+    Json(ApiResp::success(HealthData::new(
+        client.upstream_status(),
+        client.circuit_status(),
+        client.coalesce_stats(),
+    )))
+}
+
+/// 存活检查
+/// 
+/// 服务进程正常运行时始终返回200，不会访问上游。
+#[utoipa::path(
+    get,
+    path="/healthz",
+    security(()),
+    responses(
+        (status=200,description="服务存活",body=ApiResp<LivenessData>)
+    ),
+    tag = "ops"
+)]
+pub async fn healthz() -> Json<LivenessResp> {
+    Json(ApiResp::success(LivenessData {
+        status: "ok".to_string(),
+    }))
+}
+
+/// 就绪检查
+/// 
+/// 至少一个上游可以访问且熔断器未打开时返回200，否则返回503。
+/// 检查结果会被缓存，过期后返回旧结果并在后台重新检查。
+#[utoipa::path(
+    get,
+    path="/readyz",
+    security(()),
+    responses(
+        (status=200,description="服务就绪",body=ApiResp<ReadinessData>),
+        (status=503,description="服务未就绪",body=ApiResp<ReadinessData>)
+    ),
+    tag = "ops"
+)]
+pub async fn readyz(State(state): State<ProbeState>) -> Result<Response, AppError> {
+    let readiness = state.readiness.get().await?;
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    Ok((
+        status,
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(ApiResp::success(readiness.as_ref().clone())),
+    )
+        .into_response())
+}
+
+/// 版本信息
+/// 
+/// 返回crate版本号、构建时的git提交哈希与不含敏感信息的配置摘要。
+#[utoipa::path(
+    get,
+    path="/version",
+    security(()),
+    responses(
+        (status=200,description="版本信息",body=ApiResp<VersionData>)
+    ),
+    tag = "ops"
+)]
+pub async fn version(State(state): State<ProbeState>) -> Json<VersionResp> {
+    Json(ApiResp::success(state.version.as_ref().clone()))
+}
+
+/// API密钥用量
+/// 
+/// 返回当前请求使用的API密钥的速率限制、每日配额与用量。仅在启用API密钥认证时可用。
+#[utoipa::path(
+    get,
+    path="/usage",
+    responses(
+        (status=200,description="API密钥用量",body=ApiResp<UsageData>),
+        (status=401,description="缺少或无效的API密钥")
+    ),
+    tag = "others"
+)]
+pub async fn usage(Extension(caller): Extension<Caller>) -> Response {
+    (
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(ApiResp::success(caller.usage())),
+    )
+        .into_response()
+}
+
+/// Prometheus指标
+/// 
+/// 以Prometheus文本格式返回所有指标，见[`crate::metrics`]。
+#[cfg(feature = "metrics")]
+pub async fn metrics(State(client): State<RemoteApiClient>) -> Response {
+    let upstreams = client.upstream_status();
+    let circuit_open = client
+        .circuit_status()
+        .is_some_and(|c| c.state == crate::client::breaker::CircuitState::Open);
+    crate::metrics::upstream_health(upstreams.iter().map(|u| (u.base.as_str(), u.healthy)), circuit_open);
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+        crate::metrics::render(),
+    )
+        .into_response()
+}
+
+/// 生成上游接口的代理响应
+/// 
+/// 数据已经过DTO校验；启用透传时原样返回上游响应体，否则返回重新序列化的DTO。
+fn proxied<T: Serialize>(state: &AppState, resp: WithMeta<T>) -> Response {
+    if state.options.passthrough {
+        ([(header::CONTENT_TYPE, "application/json")], resp.meta.body).into_response()
+    } else {
+        Json(resp.data).into_response()
+    }
+}
+
+/// 服务对外的根地址，用于生成订阅源与播放列表中指向本服务的链接
+struct PublicOrigin {
+    /// 协议与主机（反向代理挂载路径也包含在内），不以`/`结尾，也不含路由前缀
+    origin: String,
+
+    /// 是否由请求头推断
+    /// 
+    /// 推断出的链接因请求而异，响应不能放入服务端缓存或其他共享缓存
+    inferred: bool,
+}
+
+impl PublicOrigin {
+    /// 优先使用[`RouterOptions::public_url`](super::RouterOptions::public_url)，
+    /// 未配置时按`Forwarded`、`X-Forwarded-Proto`、`X-Forwarded-Host`与`Host`请求头推断
+    fn resolve(state: &AppState, headers: &HeaderMap) -> Option<Self> {
+        if let Some(public_url) = &state.options.public_url {
+            return Some(Self {
+                origin: public_url.clone(),
+                inferred: false,
+            });
+        }
+
+        let scheme = forwarded(headers, "proto")
+            .or_else(|| first_value(headers, "x-forwarded-proto"))
+            .filter(|scheme| scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"))
+            .map_or_else(|| "http".to_string(), |scheme| scheme.to_ascii_lowercase());
+        let host = forwarded(headers, "host")
+            .or_else(|| first_value(headers, "x-forwarded-host"))
+            .or_else(|| headers.get(header::HOST)?.to_str().ok().map(str::to_string))?;
+        // 主机名来自请求头，只接受不含用户信息的合法主机与端口
+        let authority: Authority = host.parse().ok().filter(|a: &Authority| !a.as_str().contains('@'))?;
+        Some(Self {
+            origin: format!("{}://{}", scheme, authority),
+            inferred: true,
+        })
+    }
+
+    /// 本服务中`path`对应的完整地址，`path`应包含路由前缀
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.origin, path)
+    }
+}
+
+/// `Forwarded`请求头中第一个代理记录的参数
+fn forwarded(headers: &HeaderMap, name: &str) -> Option<String> {
+    let value = headers.get(header::FORWARDED)?.to_str().ok()?;
+    value.split(',').next()?.split(';').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// 以逗号分隔的请求头中的第一个值
+fn first_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    let value = headers.get(name)?.to_str().ok()?.split(',').next()?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// 根据查询参数构造新闻订阅源配置
+fn news_feed_options(q: &NewsFeedQuery, origin: Option<&PublicOrigin>, uri: &OriginalUri) -> NewsFeedOptions {
+    NewsFeedOptions {
+        self_link: origin.map(|origin| origin.url(uri.path())),
+        pages: q.pages.unwrap_or(1).clamp(1, MAX_FEED_PAGES),
+        include_content: q.content.unwrap_or(false),
+        ..NewsFeedOptions::default()
+    }
+}
+
+/// 为包含对外链接的响应设置缓存策略：链接由请求头推断时只允许客户端缓存
+fn with_origin(origin: Option<&PublicOrigin>, response: impl IntoResponse) -> Response {
+    let mut response = response.into_response();
+    if origin.is_some_and(|origin| origin.inferred) {
+        response
+            .headers_mut()
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static("private"));
+    }
+    response
+}
+
+/// 新闻RSS订阅源
+/// 
+/// 将新闻列表转换为RSS 2.0格式，可在阅读器中订阅MSR动向。
+#[utoipa::path(
+    get,
+    path="/feeds/news.rss",
+    params(NewsFeedQuery),
+    responses(
+        (status=200,description="RSS 2.0订阅源",body=String,content_type="application/rss+xml")
+    ),
+    tags=["feeds","news"],
+)]
+pub async fn news_rss(
+    Query(q): Query<NewsFeedQuery>,
+    uri: OriginalUri,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let origin = PublicOrigin::resolve(&state, &headers);
+    let options = news_feed_options(&q, origin.as_ref(), &uri);
+    let entries = news::fetch_entries(&state.client, &options).await?;
+    Ok(with_origin(
+        origin.as_ref(),
+        (
+            [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+            news::render_rss(&entries, &options),
+        ),
+    ))
+}
+
+/// 新闻Atom订阅源
+/// 
+/// 将新闻列表转换为Atom格式，可在阅读器中订阅MSR动向。
+#[utoipa::path(
+    get,
+    path="/feeds/news.atom",
+    params(NewsFeedQuery),
+    responses(
+        (status=200,description="Atom订阅源",body=String,content_type="application/atom+xml")
+    ),
+    tags=["feeds","news"],
+)]
+pub async fn news_atom(
+    Query(q): Query<NewsFeedQuery>,
+    uri: OriginalUri,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let origin = PublicOrigin::resolve(&state, &headers);
+    let options = news_feed_options(&q, origin.as_ref(), &uri);
+    let entries = news::fetch_entries(&state.client, &options).await?;
+    Ok(with_origin(
+        origin.as_ref(),
+        (
+            [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+            news::render_atom(&entries, &options),
+        ),
+    ))
+}
+
+/// 去掉订阅源文件名中的`.rss`后缀，后缀不匹配时视为资源不存在
+fn strip_rss_suffix(file: &str) -> Result<&str, AppError> {
+    file.strip_suffix(".rss")
+        .filter(|name| !name.is_empty())
+        .ok_or(AppError::NotFound)
+}
+
+/// 将播客频道渲染为RSS响应
+fn podcast_response(
+    mut channel: podcast::PodcastChannel,
+    episodes: &[podcast::PodcastEpisode],
+    state: &AppState,
+    headers: &HeaderMap,
+    uri: &OriginalUri,
+) -> Response {
+    let origin = PublicOrigin::resolve(state, headers);
+    channel.self_link = origin.as_ref().map(|origin| origin.url(uri.path()));
+    with_origin(
+        origin.as_ref(),
+        (
+            [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+            podcast::render_rss(&channel, episodes),
+        ),
+    )
+}
+
+/// 专辑播客订阅源
+/// 
+/// 将专辑中的歌曲转换为播客节目，音频文件作为节目附件。
+#[utoipa::path(
+    get,
+    path="/feeds/album/{cid}.rss",
+    params(
+        ("cid"=String,Path,description="专辑cid")
+    ),
+    responses(
+        (status=200,description="播客RSS订阅源",body=String,content_type="application/rss+xml")
+    ),
+    tags=["feeds","albums"],
+)]
+pub async fn album_podcast(
+    Path(file): Path<String>,
+    uri: OriginalUri,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let cid = strip_rss_suffix(&file)?;
+    let (channel, episodes) = podcast::fetch_album(&state.client, cid.to_string()).await?;
+    Ok(podcast_response(channel, &episodes, &state, &headers, &uri))
+}
+
+/// 艺术家播客订阅源
+/// 
+/// 将指定艺术家参与的所有歌曲转换为播客节目，音频文件作为节目附件。
+#[utoipa::path(
+    get,
+    path="/feeds/artist/{name}.rss",
+    params(
+        ("name"=String,Path,description="艺术家名称")
+    ),
+    responses(
+        (status=200,description="播客RSS订阅源",body=String,content_type="application/rss+xml"),
+        (status=404,description="没有找到该艺术家的歌曲")
+    ),
+    tags=["feeds","songs"],
+)]
+pub async fn artist_podcast(
+    Path(file): Path<String>,
+    uri: OriginalUri,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let name = strip_rss_suffix(&file)?;
+    let (channel, episodes) = podcast::fetch_artist(&state.client, name).await?;
+    Ok(podcast_response(channel, &episodes, &state, &headers, &uri))
+}
+
+/// 获取歌曲音频
+/// 
+/// 重定向到歌曲的上游音频文件，为播放列表提供稳定的代理地址。
+#[utoipa::path(
+    get,
+    path="/song/{cid}/audio",
+    params(
+        ("cid"=String,Path,description="歌曲cid")
+    ),
+    responses(
+        (status=307,description="重定向到音频文件"),
+        (status=404,description="歌曲没有音频文件")
+    ),
+    tag = "songs"
+)]
+pub async fn get_song_audio(
+    Path(cid): Path<String>,
+    State(client): State<RemoteApiClient>,
+) -> Result<Redirect, AppError> {
+    let song = client.get_song(cid).await?.data;
+    let source_url = song.source_url.ok_or(AppError::NotFound)?;
+    Ok(Redirect::temporary(&source_url))
+}
+
+/// 拉取专辑播放列表，按查询参数决定曲目地址的生成方式
+/// 
+/// 代理模式下同时返回生成曲目地址所用的对外地址。
+async fn album_playlist(
+    state: &AppState,
+    cid: String,
+    q: &PlaylistQuery,
+    headers: &HeaderMap,
+) -> Result<(Playlist, Option<PublicOrigin>), AppError> {
+    if !q.proxy.unwrap_or(false) {
+        return Ok((playlist::fetch_album(&state.client, cid, &UrlMode::Upstream).await?, None));
+    }
+    let origin = PublicOrigin::resolve(state, headers)
+        .ok_or_else(|| AppError::BadRequest("代理模式需要配置对外的访问地址或有效的Host请求头".into()))?;
+    let mode = UrlMode::Proxy(origin.url(state.options.path_prefix.as_deref().unwrap_or_default()));
+    Ok((playlist::fetch_album(&state.client, cid, &mode).await?, Some(origin)))
+}
+
+/// 专辑M3U8播放列表
+/// 
+/// 生成包含专辑所有歌曲的扩展M3U8播放列表。
+#[utoipa::path(
+    get,
+    path="/album/{cid}/playlist.m3u8",
+    params(
+        ("cid"=String,Path,description="专辑cid"),
+        PlaylistQuery
+    ),
+    responses(
+        (status=200,description="M3U8播放列表",body=String,content_type="audio/x-mpegurl")
+    ),
+    tag = "albums"
+)]
+pub async fn album_m3u8(
+    Path(cid): Path<String>,
+    Query(q): Query<PlaylistQuery>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let (playlist, origin) = album_playlist(&state, cid, &q, &headers).await?;
+    Ok(with_origin(
+        origin.as_ref(),
+        (
+            [(header::CONTENT_TYPE, "audio/x-mpegurl; charset=utf-8")],
+            playlist.to_m3u8(),
+        ),
+    ))
+}
+
+/// 专辑XSPF播放列表
+/// 
+/// 生成包含专辑所有歌曲的XSPF播放列表，包含专辑名称与封面信息。
+#[utoipa::path(
+    get,
+    path="/album/{cid}/playlist.xspf",
+    params(
+        ("cid"=String,Path,description="专辑cid"),
+        PlaylistQuery
+    ),
+    responses(
+        (status=200,description="XSPF播放列表",body=String,content_type="application/xspf+xml")
+    ),
+    tag = "albums"
+)]
+pub async fn album_xspf(
+    Path(cid): Path<String>,
+    Query(q): Query<PlaylistQuery>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let (playlist, origin) = album_playlist(&state, cid, &q, &headers).await?;
+    Ok(with_origin(
+        origin.as_ref(),
+        (
+            [(header::CONTENT_TYPE, "application/xspf+xml; charset=utf-8")],
+            playlist.to_xspf(),
+        ),
+    ))
+}
+
+/// 专辑PLS播放列表
+/// 
+/// 生成包含专辑所有歌曲的PLS播放列表。
+#[utoipa::path(
+    get,
+    path="/album/{cid}/playlist.pls",
+    params(
+        ("cid"=String,Path,description="专辑cid"),
+        PlaylistQuery
+    ),
+    responses(
+        (status=200,description="PLS播放列表",body=String,content_type="audio/x-scpls")
+    ),
+    tag = "albums"
+)]
+pub async fn album_pls(
+    Path(cid): Path<String>,
+    Query(q): Query<PlaylistQuery>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let (playlist, origin) = album_playlist(&state, cid, &q, &headers).await?;
+    Ok(with_origin(
+        origin.as_ref(),
+        (
+            [(header::CONTENT_TYPE, "audio/x-scpls; charset=utf-8")],
+            playlist.to_pls(),
+        ),
+    ))
+}
+
+/// 获取所有艺术家列表
+/// 
+/// 聚合歌曲和专辑中出现的艺术家，全角/半角与空白不同的写法会被合并。
+#[utoipa::path(
+    get,
+    path="/artists",
+    responses(
+        (status=200,description="艺术家列表",body=ApiResp<Vec<ArtistSummary>>)
+    ),
+    tag = "artists"
+)]
+pub async fn list_artists(
+    State(state): State<AppState>,
+) -> Result<Json<ArtistListResp>, AppError> {
+    let index = state.index.get().await?;
+    Ok(Json(ApiResp::success(index.artists.list_artists())))
+}
+
+/// 获取艺术家详情
+/// 
+/// 根据艺术家名称（任意一种写法）获取其参与的所有专辑和歌曲。
+#[utoipa::path(
+    get,
+    path="/artist/{name}",
+    params(
+        ("name"=String,Path,description="艺术家名称")
+    ),
+    responses(
+        (status=200,description="艺术家详情",body=ApiResp<ArtistDetail>),
+        (status=404,description="艺术家不存在")
+    ),
+    tag = "artists"
+)]
+pub async fn get_artist(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<ArtistResp>, AppError> {
+    let index = state.index.get().await?;
+    index
+        .artists
+        .get_artist(&name)
+        .map(|artist| Json(ApiResp::success(artist)))
+        .ok_or(AppError::NotFound)
 }
@@ -0,0 +1,252 @@
+//! # 中间件
+//!
+//! 定义了作用于所有路由的Axum中间件。
+//!
+//! [`request_id`]为每个请求分配请求ID，[`trace_request`]为每个请求创建span，
+//! 记录路由、路径、查询参数与响应状态码，并在请求完成后输出访问日志；
+//! 启用`otel` feature时还会从请求头`traceparent`中提取上游链路。
+//!
+//! [`cors_layer`]、[`compression_layer`]与[`security_headers`]按配置启用，见[`RouterOptions`](super::RouterOptions)。
+
+use crate::client::upstream::{time_upstreams, track_upstreams};
+use crate::config::reload::LiveConfig;
+use crate::config::{CompressionConfig, CorsConfig, SecurityHeadersConfig};
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, header},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::Instrument;
+
+/// 标明提供响应的上游地址的响应头
+pub const UPSTREAM_HEADER: HeaderName = HeaderName::from_static("x-upstream");
+
+/// 请求ID的请求头与响应头
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// 请求ID的最大长度，更长的请求头会被替换为新生成的ID
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// 当前请求的ID，不在[`request_id`]中间件处理的请求中时为`None`
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// 为每个请求分配请求ID
+///
+/// 沿用请求头`X-Request-Id`（不超过128个可见ASCII字符），否则生成UUID。
+/// 请求ID会写回请求头与响应头，并可在处理请求期间通过[`current_request_id`]获取。
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic()))
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let value = HeaderValue::from_str(&id).expect("请求ID只包含可见ASCII字符");
+    request.headers_mut().insert(REQUEST_ID_HEADER, value.clone());
+    let mut response = REQUEST_ID.scope(id, next.run(request)).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    response
+}
+
+/// 按配置创建CORS中间件
+///
+/// 允许任意请求头（包括携带API密钥的请求头），并暴露`X-Upstream`、`X-Request-Id`与`Retry-After`响应头。
+pub fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    let origins = if config.allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(
+            config
+                .allowed_origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+        )
+    };
+    cors_with_origins(config, origins)
+}
+
+/// 创建允许的来源取自`live`的CORS中间件，配置重新加载后立即生效
+///
+/// 允许的方法与预检结果的缓存时间取自`config`，不随重新加载变化。
+pub fn live_cors_layer(config: &CorsConfig, live: LiveConfig) -> CorsLayer {
+    let origins = AllowOrigin::predicate(move |origin, _| {
+        live.load().cors.as_ref().is_some_and(|cors| {
+            cors.allowed_origins
+                .iter()
+                .any(|allowed| allowed == "*" || origin.as_bytes() == allowed.as_bytes())
+        })
+    });
+    cors_with_origins(config, origins)
+}
+
+fn cors_with_origins(config: &CorsConfig, origins: AllowOrigin) -> CorsLayer {
+    let methods: Vec<Method> = config
+        .allowed_methods
+        .iter()
+        .filter_map(|method| Method::from_bytes(method.as_bytes()).ok())
+        .collect();
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(methods)
+        .allow_headers(tower_http::cors::Any)
+        .expose_headers([UPSTREAM_HEADER, REQUEST_ID_HEADER, header::RETRY_AFTER])
+        .max_age(config.max_age)
+}
+
+/// 按配置创建响应压缩中间件
+///
+/// 按请求头`Accept-Encoding`选择启用的压缩算法；小于`min_size`的响应以及图片、gRPC与SSE响应不压缩。
+pub fn compression_layer(config: &CompressionConfig) -> CompressionLayer<impl Predicate + use<>> {
+    let predicate = SizeAbove::new(config.min_size)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::SSE);
+    CompressionLayer::new()
+        .gzip(config.gzip)
+        .br(config.br)
+        .zstd(config.zstd)
+        .no_deflate()
+        .compress_when(predicate)
+}
+
+/// 按配置生成的安全响应头
+#[derive(Debug, Clone)]
+pub struct SecurityHeaders {
+    headers: Arc<HeaderMap>,
+}
+
+impl SecurityHeaders {
+    /// 生成安全响应头，`content_security_policy`不是有效的响应头值时忽略
+    pub fn new(config: &SecurityHeadersConfig) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+        headers.insert(header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY"));
+        headers.insert(header::REFERRER_POLICY, HeaderValue::from_static("no-referrer"));
+        if let Some(max_age) = config.hsts_max_age
+            && let Ok(value) = HeaderValue::try_from(format!("max-age={}", max_age.as_secs()))
+        {
+            headers.insert(header::STRICT_TRANSPORT_SECURITY, value);
+        }
+        if let Some(policy) = config
+            .content_security_policy
+            .as_deref()
+            .and_then(|policy| HeaderValue::from_str(policy).ok())
+        {
+            headers.insert(header::CONTENT_SECURITY_POLICY, policy);
+        }
+        Self {
+            headers: Arc::new(headers),
+        }
+    }
+}
+
+/// 为所有响应添加安全响应头，不覆盖处理函数已设置的同名响应头
+pub async fn security_headers(State(security): State<SecurityHeaders>, request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    for (name, value) in security.headers.iter() {
+        if !response.headers().contains_key(name) {
+            response.headers_mut().insert(name, value.clone());
+        }
+    }
+    response
+}
+
+/// 在响应头`X-Upstream`中报告处理请求期间实际提供响应的上游地址
+///
+/// 请求涉及多个上游时以逗号分隔；未访问上游（如命中本地缓存）时不添加该响应头。
+pub async fn report_upstream(request: Request, next: Next) -> Response {
+    let (mut response, upstreams) = track_upstreams(next.run(request)).await;
+    if !upstreams.is_empty()
+        && let Ok(value) = HeaderValue::from_str(&upstreams.join(", "))
+    {
+        response.headers_mut().insert(UPSTREAM_HEADER, value);
+    }
+    response
+}
+
+/// 记录各路由的请求数、处理耗时与正在处理的请求数
+///
+/// 路由标签为路由模板（如`/song/{cid}`），没有匹配的路由时为`unmatched`。
+#[cfg(feature = "metrics")]
+pub async fn track_metrics(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", |path| path.as_str())
+        .to_string();
+    let method = request.method().clone();
+    let started = std::time::Instant::now();
+    let in_flight = crate::metrics::http_started(&route);
+    let response = next.run(request).await;
+    drop(in_flight);
+    crate::metrics::http_finished(&route, method.as_str(), response.status().as_u16(), started.elapsed());
+    response
+}
+
+/// 为每个请求创建span，记录路由与响应状态码，并输出访问日志
+///
+/// 路由为路由模板（如`/song/{cid}`），cid与搜索关键词分别见`url.path`与`url.query`。
+/// span带有[`request_id`]分配的请求ID，处理请求期间的所有日志都会带上该ID。
+/// 访问日志包含路由、方法、状态码、处理耗时与访问上游的累计耗时（毫秒）。
+pub async fn trace_request(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", |path| path.as_str())
+        .to_string();
+    let method = request.method().clone();
+    let span = tracing::info_span!(
+        "http_request",
+        otel.kind = "server",
+        otel.name = %format!("{} {}", method, route),
+        request_id = current_request_id(),
+        http.request.method = %method,
+        http.route = route,
+        url.path = request.uri().path(),
+        url.query = request.uri().query(),
+        api_key = tracing::field::Empty,
+        http.response.status_code = tracing::field::Empty,
+        otel.status_code = tracing::field::Empty,
+    );
+    #[cfg(feature = "otel")]
+    crate::telemetry::set_parent(&span, request.headers());
+
+    async move {
+        let started = Instant::now();
+        let (response, upstream) = time_upstreams(next.run(request)).await;
+        let span = tracing::Span::current();
+        span.record("http.response.status_code", response.status().as_u16());
+        if response.status().is_server_error() {
+            span.record("otel.status_code", "error");
+        }
+        tracing::info!(
+            route = %route,
+            method = %method,
+            status = response.status().as_u16(),
+            latency_ms = millis(started.elapsed()),
+            upstream_ms = millis(upstream),
+            "请求完成"
+        );
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+/// 精确到微秒的毫秒数
+fn millis(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1000.0
+}
@@ -0,0 +1,226 @@
+//! # 路由状态
+//!
+//! 定义了Web路由共享的状态，以及过期后在后台刷新的数据缓存。
+
+use crate::artist::ArtistIndex;
+use crate::catalog::Catalog;
+use crate::client::remote::RemoteApiClient;
+use crate::dto::{ReadinessData, VersionData};
+use crate::error::AppError;
+use crate::search::SearchIndex;
+use crate::tasks::Tasks;
+use crate::web::{ProbeOptions, RouterOptions};
+use axum::extract::FromRef;
+use futures::future::BoxFuture;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// 曲库索引的刷新间隔
+const MUSIC_INDEX_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// 数据加载函数
+type Loader<T> = Box<dyn Fn() -> BoxFuture<'static, Result<T, AppError>> + Send + Sync>;
+
+/// 过期后在后台刷新的数据缓存
+///
+/// 首次访问时同步加载数据；之后数据超过`ttl`时，访问会立即返回旧数据，
+/// 同时在后台发起一次刷新（stale-while-revalidate）。刷新失败时保留旧数据。
+/// 没有访问时不会刷新，数据可能远早于`ttl`之前加载。
+///
+/// 克隆后的实例共享同一份数据。后台刷新在创建时指定的任务组中进行。
+pub struct Refreshing<T> {
+    inner: Arc<RefreshingInner<T>>,
+}
+
+struct RefreshingInner<T> {
+    value: RwLock<Option<(Instant, Arc<T>)>>,
+    refreshing: AtomicBool,
+    cold_load: tokio::sync::Mutex<()>,
+    ttl: Duration,
+    tasks: Tasks,
+    loader: Loader<T>,
+}
+
+impl<T> Clone for Refreshing<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> Refreshing<T> {
+    /// 创建缓存
+    ///
+    /// # 参数
+    ///
+    /// * `ttl` - 数据的有效期，超过后在后台刷新
+    /// * `tasks` - 后台刷新任务所属的任务组
+    /// * `loader` - 数据加载函数
+    pub fn new<F, Fut>(ttl: Duration, tasks: Tasks, loader: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, AppError>> + Send + 'static,
+    {
+        Self {
+            inner: Arc::new(RefreshingInner {
+                value: RwLock::new(None),
+                refreshing: AtomicBool::new(false),
+                cold_load: tokio::sync::Mutex::new(()),
+                ttl,
+                tasks,
+                loader: Box::new(move || Box::pin(loader())),
+            }),
+        }
+    }
+
+    /// 获取数据，必要时加载或在后台刷新
+    pub async fn get(&self) -> Result<Arc<T>, AppError> {
+        if let Some(value) = self.current() {
+            return Ok(value);
+        }
+
+        // 冷启动时只允许一个请求加载数据，其余请求等待其结果
+        let _guard = self.inner.cold_load.lock().await;
+        if let Some(value) = self.current() {
+            return Ok(value);
+        }
+        let value = Arc::new((self.inner.loader)().await?);
+        self.store(value.clone());
+        Ok(value)
+    }
+
+    /// 返回已缓存的数据，数据过期时触发后台刷新
+    fn current(&self) -> Option<Arc<T>> {
+        let (loaded_at, value) = self.inner.value.read().ok()?.clone()?;
+        if loaded_at.elapsed() >= self.inner.ttl {
+            self.spawn_refresh();
+        }
+        Some(value)
+    }
+
+    fn store(&self, value: Arc<T>) {
+        if let Ok(mut slot) = self.inner.value.write() {
+            *slot = Some((Instant::now(), value));
+        }
+    }
+
+    /// 在后台刷新数据，同一时间只会有一个刷新任务
+    fn spawn_refresh(&self) {
+        if self.inner.refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let guard = RefreshGuard(self.inner.clone());
+        let this = self.clone();
+        self.inner.tasks.spawn(async move {
+            let _guard = guard;
+            match (this.inner.loader)().await {
+                Ok(value) => this.store(Arc::new(value)),
+                Err(e) => tracing::warn!("后台刷新缓存失败: {}", e),
+            }
+        });
+    }
+}
+
+/// 刷新任务结束时清除刷新标记，任务panic或被取消时同样清除，使之后的访问可以再次发起刷新
+struct RefreshGuard<T>(Arc<RefreshingInner<T>>);
+
+impl<T> Drop for RefreshGuard<T> {
+    fn drop(&mut self) {
+        self.0.refreshing.store(false, Ordering::Release);
+    }
+}
+
+/// 由歌曲与专辑列表构建的曲库索引
+#[derive(Debug, Clone, Default)]
+pub struct MusicIndex {
+    /// 本地搜索索引（不含专辑简介与新闻）
+    pub search: SearchIndex,
+
+    /// 艺术家索引
+    pub artists: ArtistIndex,
+}
+
+impl MusicIndex {
+    /// 从远程API拉取歌曲与专辑列表并构建索引
+    pub async fn fetch(client: &RemoteApiClient) -> Result<Self, AppError> {
+        let (songs, albums) = futures::try_join!(client.get_all_songs(), client.get_all_albums())?;
+        let songs = songs.data.list;
+        let albums = albums.data;
+        Ok(Self {
+            search: SearchIndex::build(&Catalog::from_lists(songs.clone(), albums.clone())),
+            artists: ArtistIndex::build(songs, albums),
+        })
+    }
+}
+
+/// Web路由共享的状态
+///
+/// 处理函数可以直接提取`State<RemoteApiClient>`，也可以提取完整的`State<AppState>`。
+#[derive(Clone)]
+pub struct AppState {
+    /// 远程API客户端
+    pub client: RemoteApiClient,
+
+    /// 曲库索引（歌曲搜索与艺术家），超过10分钟后在下一次访问时于后台刷新
+    pub index: Refreshing<MusicIndex>,
+
+    /// 路由选项
+    pub options: RouterOptions,
+}
+
+impl AppState {
+    /// 使用远程API客户端创建路由状态，路由选项为默认值
+    pub fn new(client: RemoteApiClient) -> Self {
+        Self::with_options(client, RouterOptions::default())
+    }
+
+    /// 使用远程API客户端与路由选项创建路由状态
+    pub fn with_options(client: RemoteApiClient, options: RouterOptions) -> Self {
+        let loader_client = client.clone();
+        let index = Refreshing::new(MUSIC_INDEX_TTL, client.tasks().clone(), move || {
+            let client = loader_client.clone();
+            async move { MusicIndex::fetch(&client).await }
+        });
+        Self {
+            client,
+            index,
+            options,
+        }
+    }
+}
+
+impl FromRef<AppState> for RemoteApiClient {
+    fn from_ref(state: &AppState) -> Self {
+        state.client.clone()
+    }
+}
+
+/// 运维接口（存活、就绪与版本）共享的状态
+#[derive(Clone)]
+pub struct ProbeState {
+    /// 缓存的就绪检查结果，过期后在后台重新检查
+    pub readiness: Refreshing<ReadinessData>,
+
+    /// 版本信息
+    pub version: Arc<VersionData>,
+}
+
+impl ProbeState {
+    /// 使用远程API客户端与运维接口选项创建状态
+    pub fn new(client: RemoteApiClient, options: ProbeOptions) -> Self {
+        let readiness = Refreshing::new(options.readiness_ttl, client.tasks().clone(), move || {
+            let client = client.clone();
+            async move {
+                let upstreams = client.check_upstreams().await;
+                Ok(ReadinessData::new(upstreams, client.circuit_status()))
+            }
+        });
+        Self {
+            readiness,
+            version: Arc::new(VersionData::new(options.config)),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! API密钥认证、速率限制与每日配额
+
+#![cfg(feature = "web")]
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use axum::response::Response;
+use easy_msr_api::client::limiter::RateLimitConfig;
+use easy_msr_api::client::remote::RemoteApiClient;
+use easy_msr_api::config::{ApiKey, AuthConfig, CacheConfig, CachePolicy};
+use easy_msr_api::web::{self, RouterOptions};
+use std::time::Duration;
+use tower::ServiceExt;
+
+fn song() -> String {
+    serde_json::json!({
+        "code": 0,
+        "msg": "",
+        "data": {
+            "cid": "953953",
+            "name": "Little Wish",
+            "albumCid": "3888",
+            "sourceUrl": null,
+            "lyricUrl": null,
+            "mvUrl": null,
+            "mvCoverUrl": null,
+            "artists": ["塞壬唱片-MSR"]
+        }
+    })
+    .to_string()
+}
+
+fn key(name: &str, key: &str) -> ApiKey {
+    ApiKey {
+        name: name.into(),
+        key: key.into(),
+        rate_limit: None,
+        daily_quota: None,
+    }
+}
+
+fn app(upstream: &mockito::Server, keys: Vec<ApiKey>, cache: Option<CacheConfig>) -> axum::Router {
+    web::routes_with_options(
+        RemoteApiClient::new(upstream.url()),
+        RouterOptions {
+            auth: Some(AuthConfig {
+                keys,
+                header: "X-API-Key".into(),
+                query_param: Some("api_key".into()),
+            }),
+            cache,
+            ..Default::default()
+        },
+    )
+}
+
+async fn send(app: &axum::Router, uri: &str, api_key: Option<&str>) -> Response {
+    let mut request = Request::get(uri);
+    if let Some(api_key) = api_key {
+        request = request.header("X-API-Key", api_key);
+    }
+    app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+}
+
+async fn json(response: Response) -> serde_json::Value {
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn missing_or_invalid_key_is_rejected() {
+    let mut upstream = mockito::Server::new_async().await;
+    let song = upstream
+        .mock("GET", "/song/953953")
+        .with_header("content-type", "application/json")
+        .with_body(song())
+        .expect(2)
+        .create_async()
+        .await;
+    let app = app(&upstream, vec![key("web", "web-secret")], None);
+
+    assert_eq!(send(&app, "/song/953953", None).await.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(send(&app, "/song/953953", Some("wrong")).await.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(send(&app, "/song/953953?api_key=wrong", None).await.status(), StatusCode::UNAUTHORIZED);
+    // 两者都有时使用请求头
+    assert_eq!(
+        send(&app, "/song/953953?api_key=web-secret", Some("wrong")).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    assert_eq!(send(&app, "/song/953953", Some("web-secret")).await.status(), StatusCode::OK);
+    assert_eq!(send(&app, "/song/953953?api_key=web-secret", None).await.status(), StatusCode::OK);
+    song.assert_async().await;
+}
+
+#[tokio::test]
+async fn query_key_is_removed_from_uri() {
+    let mut upstream = mockito::Server::new_async().await;
+    let song = upstream
+        .mock("GET", "/song/953953")
+        .with_header("content-type", "application/json")
+        .with_body(song())
+        .expect(1)
+        .create_async()
+        .await;
+    let cache = CacheConfig {
+        default_policy: Some(CachePolicy {
+            max_age: Duration::from_secs(60),
+            stale_while_revalidate: Duration::ZERO,
+        }),
+        ..Default::default()
+    };
+    let app = app(&upstream, vec![key("a", "secret-a"), key("b", "secret-b")], Some(cache));
+
+    // 缓存的键为URI，密钥被移除后两个请求命中同一条缓存
+    let first = send(&app, "/song/953953?api_key=secret-a", None).await;
+    assert_eq!(first.status(), StatusCode::OK);
+    let second = send(&app, "/song/953953?api_key=secret-b", None).await;
+    assert_eq!(second.status(), StatusCode::OK);
+    assert!(second.headers().contains_key(header::AGE));
+    song.assert_async().await;
+}
+
+#[tokio::test]
+async fn rate_limited_key_gets_retry_after() {
+    let mut upstream = mockito::Server::new_async().await;
+    upstream
+        .mock("GET", "/song/953953")
+        .with_header("content-type", "application/json")
+        .with_body(song())
+        .create_async()
+        .await;
+    let mut slow = key("slow", "slow-secret");
+    slow.rate_limit = Some(RateLimitConfig {
+        requests_per_second: 1.0,
+        burst: 1,
+        max_concurrency: None,
+    });
+    let app = app(&upstream, vec![slow, key("web", "web-secret")], None);
+
+    assert_eq!(send(&app, "/song/953953", Some("slow-secret")).await.status(), StatusCode::OK);
+    let limited = send(&app, "/song/953953", Some("slow-secret")).await;
+    assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = limited.headers()[header::RETRY_AFTER].to_str().unwrap().parse().unwrap();
+    assert!((1..=2).contains(&retry_after));
+
+    // 速率限制只针对该密钥
+    assert_eq!(send(&app, "/song/953953", Some("web-secret")).await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn daily_quota_excludes_usage_requests() {
+    let mut upstream = mockito::Server::new_async().await;
+    let song = upstream
+        .mock("GET", "/song/953953")
+        .with_header("content-type", "application/json")
+        .with_body(song())
+        .expect(2)
+        .create_async()
+        .await;
+    let mut trial = key("trial", "trial-secret");
+    trial.daily_quota = Some(2);
+    let app = app(&upstream, vec![trial], None);
+
+    assert_eq!(send(&app, "/usage", None).await.status(), StatusCode::UNAUTHORIZED);
+    for _ in 0..3 {
+        assert_eq!(send(&app, "/usage", Some("trial-secret")).await.status(), StatusCode::OK);
+    }
+    for _ in 0..2 {
+        assert_eq!(send(&app, "/song/953953", Some("trial-secret")).await.status(), StatusCode::OK);
+    }
+    let exceeded = send(&app, "/song/953953", Some("trial-secret")).await;
+    assert_eq!(exceeded.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(exceeded.headers().contains_key(header::RETRY_AFTER));
+    song.assert_async().await;
+
+    // 配额用完后仍可查看用量
+    let usage = json(send(&app, "/usage", Some("trial-secret")).await).await;
+    assert_eq!(usage["data"]["name"], "trial");
+    assert_eq!(usage["data"]["totalRequests"], 2);
+    assert_eq!(usage["data"]["rejectedRequests"], 1);
+    assert_eq!(usage["data"]["remainingToday"], 0);
+}
+
+#[tokio::test]
+async fn docs_stay_public() {
+    let upstream = mockito::Server::new_async().await;
+    let app = app(&upstream, vec![key("web", "web-secret")], None);
+
+    let openapi = send(&app, "/api-docs/openapi.json", None).await;
+    assert_eq!(openapi.status(), StatusCode::OK);
+    assert!(json(openapi).await["paths"]["/usage"].is_object());
+    assert_eq!(send(&app, "/swagger-ui/", None).await.status(), StatusCode::OK);
+    assert_eq!(send(&app, "/swagger-ui/index.html", None).await.status(), StatusCode::OK);
+}
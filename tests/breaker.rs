@@ -0,0 +1,80 @@
+//! 熔断器的状态转换
+
+use easy_msr_api::client::breaker::{CircuitBreakerConfig, CircuitState};
+use easy_msr_api::client::remote::RemoteApiClient;
+use easy_msr_api::error::AppError;
+use std::time::Duration;
+
+const COOLDOWN: Duration = Duration::from_millis(300);
+
+fn song() -> String {
+    serde_json::json!({
+        "code": 0,
+        "msg": "",
+        "data": {
+            "cid": "953953",
+            "name": "Little Wish",
+            "albumCid": "3888",
+            "sourceUrl": null,
+            "lyricUrl": null,
+            "mvUrl": null,
+            "mvCoverUrl": null,
+            "artists": ["塞壬唱片-MSR"]
+        }
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn breaker_opens_probes_and_recovers() {
+    let mut upstream = mockito::Server::new_async().await;
+    let failing = upstream
+        .mock("GET", "/song/953953")
+        .with_status(500)
+        .expect(3)
+        .create_async()
+        .await;
+    let client = RemoteApiClient::new(upstream.url()).with_circuit_breaker(CircuitBreakerConfig {
+        failure_threshold: 2,
+        cooldown: COOLDOWN,
+        half_open_probes: 1,
+    });
+    let state = || client.circuit_status().unwrap().state;
+
+    // 连续失败达到阈值后打开
+    assert!(matches!(client.get_song("953953".into()).await, Err(AppError::Remote(_))));
+    assert_eq!(state(), CircuitState::Closed);
+    assert!(matches!(client.get_song("953953".into()).await, Err(AppError::Remote(_))));
+    assert_eq!(state(), CircuitState::Open);
+
+    // 冷却期内直接拒绝，不访问上游
+    match client.get_song("953953".into()).await {
+        Err(AppError::CircuitOpen { retry_after }) => assert!(retry_after <= COOLDOWN),
+        other => panic!("熔断打开时应快速失败: {:?}", other.map(|_| ())),
+    }
+
+    // 冷却结束后放行一个探测请求，探测失败时重新打开
+    tokio::time::sleep(COOLDOWN).await;
+    assert_eq!(state(), CircuitState::HalfOpen);
+    assert!(matches!(client.get_song("953953".into()).await, Err(AppError::Remote(_))));
+    assert_eq!(state(), CircuitState::Open);
+    assert!(matches!(client.get_song("953953".into()).await, Err(AppError::CircuitOpen { .. })));
+    failing.assert_async().await;
+    failing.remove_async().await;
+
+    // 上游恢复后探测成功，熔断关闭
+    let healthy = upstream
+        .mock("GET", "/song/953953")
+        .with_header("content-type", "application/json")
+        .with_body(song())
+        .expect(2)
+        .create_async()
+        .await;
+    tokio::time::sleep(COOLDOWN).await;
+    assert_eq!(client.get_song("953953".into()).await.unwrap().data.id, "953953");
+    let status = client.circuit_status().unwrap();
+    assert_eq!(status.state, CircuitState::Closed);
+    assert_eq!(status.consecutive_failures, 0);
+    assert!(client.get_song("953953".into()).await.is_ok());
+    healthy.assert_async().await;
+}
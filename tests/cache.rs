@@ -0,0 +1,84 @@
+//! 响应缓存的后台刷新
+
+#![cfg(feature = "web")]
+
+use axum::body::{Body, to_bytes};
+use axum::http::Request;
+use easy_msr_api::client::remote::RemoteApiClient;
+use easy_msr_api::config::{CacheConfig, CachePolicy};
+use easy_msr_api::web::{self, RouterOptions};
+use std::time::Duration;
+use tower::ServiceExt;
+
+fn song(name: &str) -> String {
+    serde_json::json!({
+        "code": 0,
+        "msg": "",
+        "data": {
+            "cid": "953953",
+            "name": name,
+            "albumCid": "3888",
+            "sourceUrl": "https://res01.hycdn.cn/953953.wav",
+            "lyricUrl": null,
+            "mvUrl": null,
+            "mvCoverUrl": null,
+            "artists": ["塞壬唱片-MSR"]
+        }
+    })
+    .to_string()
+}
+
+async fn get(app: &axum::Router, uri: &str) -> String {
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn stale_song_entry_is_refreshed_in_background() {
+    let mut upstream = mockito::Server::new_async().await;
+    let old = upstream
+        .mock("GET", "/song/953953")
+        .with_header("content-type", "application/json")
+        .with_body(song("Little Wish"))
+        .create_async()
+        .await;
+
+    // 缓存立即过期，之后的请求先返回旧响应，同时在后台刷新
+    let cache = CacheConfig {
+        default_policy: Some(CachePolicy {
+            max_age: Duration::ZERO,
+            stale_while_revalidate: Duration::from_secs(60),
+        }),
+        ..Default::default()
+    };
+    let app = web::routes_with_options(
+        RemoteApiClient::new(upstream.url()),
+        RouterOptions {
+            cache: Some(cache),
+            ..Default::default()
+        },
+    );
+
+    assert!(get(&app, "/song/953953").await.contains("Little Wish"));
+    old.remove_async().await;
+    let new = upstream
+        .mock("GET", "/song/953953")
+        .with_header("content-type", "application/json")
+        .with_body(song("Renegade"))
+        .create_async()
+        .await;
+
+    assert!(get(&app, "/song/953953").await.contains("Little Wish"));
+    let mut refreshed = false;
+    for _ in 0..50 {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        if get(&app, "/song/953953").await.contains("Renegade") {
+            refreshed = true;
+            break;
+        }
+    }
+    assert!(refreshed, "过期的缓存没有在后台刷新");
+    new.assert_async().await;
+}
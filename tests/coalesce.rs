@@ -0,0 +1,100 @@
+//! 相同并发请求的合并（single-flight）
+
+use easy_msr_api::client::middleware::HttpService;
+use easy_msr_api::client::remote::RemoteApiClient;
+use easy_msr_api::error::AppError;
+use std::time::Duration;
+use tower::Service;
+use tower::util::MapFutureLayer;
+
+const CALLERS: usize = 5;
+
+fn song() -> String {
+    serde_json::json!({
+        "code": 0,
+        "msg": "",
+        "data": {
+            "cid": "953953",
+            "name": "Little Wish",
+            "albumCid": "3888",
+            "sourceUrl": null,
+            "lyricUrl": null,
+            "mvUrl": null,
+            "mvCoverUrl": null,
+            "artists": ["塞壬唱片-MSR"]
+        }
+    })
+    .to_string()
+}
+
+/// 每个出站请求延迟200毫秒才发出，使并发的相同请求在进行中相遇
+fn slow_client(base: String) -> RemoteApiClient {
+    RemoteApiClient::new(base).with_layer(MapFutureLayer::new(
+        |request: <HttpService as Service<reqwest::Request>>::Future| async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            request.await
+        },
+    ))
+}
+
+#[tokio::test]
+async fn concurrent_identical_requests_hit_upstream_once() {
+    let mut upstream = mockito::Server::new_async().await;
+    let song = upstream
+        .mock("GET", "/song/953953")
+        .with_header("content-type", "application/json")
+        .with_body(song())
+        .expect(1)
+        .create_async()
+        .await;
+    let client = slow_client(upstream.url());
+
+    let requests = (0..CALLERS).map(|_| client.get_song("953953".into()));
+    for result in futures::future::join_all(requests).await {
+        assert_eq!(result.unwrap().data.id, "953953");
+    }
+    song.assert_async().await;
+    let stats = client.coalesce_stats();
+    assert_eq!(stats.upstream, 1);
+    assert_eq!(stats.coalesced, CALLERS as u64 - 1);
+
+    // 请求完成后不再合并
+    let again = upstream
+        .mock("GET", "/song/953953")
+        .with_header("content-type", "application/json")
+        .with_body(self::song())
+        .expect(1)
+        .create_async()
+        .await;
+    client.get_song("953953".into()).await.unwrap();
+    again.assert_async().await;
+}
+
+#[tokio::test]
+async fn coalesced_callers_share_the_error() {
+    let mut upstream = mockito::Server::new_async().await;
+    let failing = upstream
+        .mock("GET", "/song/953953")
+        .with_status(500)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = slow_client(upstream.url());
+
+    let requests = (0..CALLERS).map(|_| client.get_song("953953".into()));
+    let results = futures::future::join_all(requests).await;
+    failing.assert_async().await;
+
+    let mut coalesced = 0;
+    for result in results {
+        match result {
+            Err(AppError::Coalesced(inner)) => {
+                assert!(matches!(*inner, AppError::Remote(_)));
+                coalesced += 1;
+            }
+            Err(AppError::Remote(_)) => {}
+            other => panic!("上游失败时应返回上游错误: {:?}", other.map(|_| ())),
+        }
+    }
+    assert!(coalesced >= CALLERS - 1);
+}
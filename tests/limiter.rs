@@ -0,0 +1,161 @@
+//! 客户端限流：上游429时的暂停与重试，以及并发上限
+
+use easy_msr_api::client::limiter::RateLimitConfig;
+use easy_msr_api::client::middleware::HttpService;
+use easy_msr_api::client::remote::RemoteApiClient;
+use easy_msr_api::error::AppError;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tower::Service;
+use tower::util::MapFutureLayer;
+
+fn song() -> String {
+    serde_json::json!({
+        "code": 0,
+        "msg": "",
+        "data": {
+            "cid": "953953",
+            "name": "Little Wish",
+            "albumCid": "3888",
+            "sourceUrl": null,
+            "lyricUrl": null,
+            "mvUrl": null,
+            "mvCoverUrl": null,
+            "artists": ["塞壬唱片-MSR"]
+        }
+    })
+    .to_string()
+}
+
+/// 只限制并发、不限制速率的限流配置
+fn limiter(max_concurrency: Option<usize>) -> RateLimitConfig {
+    RateLimitConfig {
+        requests_per_second: 0.0,
+        burst: 1,
+        max_concurrency,
+    }
+}
+
+/// 前`times`个请求返回`429`与`Retry-After: 1`
+async fn rate_limit_first(server: &mut mockito::Server, path: &str, times: usize) -> mockito::Mock {
+    let seen = AtomicUsize::new(0);
+    server
+        .mock("GET", path)
+        .match_request(move |_| seen.fetch_add(1, Ordering::SeqCst) < times)
+        .with_status(429)
+        .with_header("retry-after", "1")
+        .expect(times)
+        .create_async()
+        .await
+}
+
+#[tokio::test]
+async fn rate_limited_request_waits_and_retries() {
+    let mut upstream = mockito::Server::new_async().await;
+    let limited = rate_limit_first(&mut upstream, "/song/953953", 1).await;
+    let served = upstream
+        .mock("GET", "/song/953953")
+        .with_header("content-type", "application/json")
+        .with_body(song())
+        .expect(1)
+        .create_async()
+        .await;
+    let albums = upstream
+        .mock("GET", "/albums")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":0,"msg":"","data":[]}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = RemoteApiClient::new(upstream.url()).with_rate_limit(limiter(None));
+
+    let started = Instant::now();
+    let song = tokio::spawn({
+        let client = client.clone();
+        async move { client.get_song("953953".into()).await }
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    // 限流器暂停期间，其他请求同样等待
+    client.get_all_albums().await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(900));
+    assert_eq!(song.await.unwrap().unwrap().data.id, "953953");
+    assert!(started.elapsed() >= Duration::from_millis(900));
+    limited.assert_async().await;
+    served.assert_async().await;
+    albums.assert_async().await;
+}
+
+#[tokio::test]
+async fn rate_limited_without_limiter_returns_error() {
+    let mut upstream = mockito::Server::new_async().await;
+    let limited = rate_limit_first(&mut upstream, "/song/953953", 1).await;
+    let client = RemoteApiClient::new(upstream.url());
+
+    match client.get_song("953953".into()).await {
+        Err(AppError::RateLimited { retry_after }) => assert_eq!(retry_after, Duration::from_secs(1)),
+        other => panic!("未启用限流时应直接返回429: {:?}", other.map(|_| ())),
+    }
+    limited.assert_async().await;
+}
+
+#[tokio::test]
+async fn long_retry_after_is_not_retried() {
+    let mut upstream = mockito::Server::new_async().await;
+    let limited = upstream
+        .mock("GET", "/song/953953")
+        .with_status(429)
+        .with_header("retry-after", "120")
+        .expect(1)
+        .create_async()
+        .await;
+    let client = RemoteApiClient::new(upstream.url()).with_rate_limit(limiter(None));
+
+    let started = Instant::now();
+    assert!(matches!(
+        client.get_song("953953".into()).await,
+        Err(AppError::RateLimited { .. })
+    ));
+    assert!(started.elapsed() < Duration::from_secs(1));
+    limited.assert_async().await;
+}
+
+#[tokio::test]
+async fn concurrency_is_capped() {
+    let mut upstream = mockito::Server::new_async().await;
+    let mock = upstream
+        .mock("GET", mockito::Matcher::Regex(r"^/song/\d+$".into()))
+        .with_header("content-type", "application/json")
+        .with_body(song())
+        .expect(6)
+        .create_async()
+        .await;
+
+    // 在出站中间件中统计同时进行的请求数，并让每个请求至少持续50毫秒
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let gauge_peak = peak.clone();
+    let gauge = MapFutureLayer::new(move |request: <HttpService as Service<reqwest::Request>>::Future| {
+        let in_flight = in_flight.clone();
+        let peak = gauge_peak.clone();
+        async move {
+            let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(current, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let response = request.await;
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            response
+        }
+    });
+    let client = RemoteApiClient::new(upstream.url())
+        .with_coalescing(false)
+        .with_layer(gauge)
+        .with_rate_limit(limiter(Some(2)));
+
+    let requests = (0..6).map(|i| client.get_song(i.to_string()));
+    for song in futures::future::join_all(requests).await {
+        song.unwrap();
+    }
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+    mock.assert_async().await;
+}
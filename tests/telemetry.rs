@@ -0,0 +1,83 @@
+//! OpenTelemetry链路导出与传播
+
+#![cfg(all(feature = "otel", feature = "web"))]
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use easy_msr_api::client::remote::RemoteApiClient;
+use easy_msr_api::web::{self, RouterOptions};
+use mockito::Matcher;
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+
+#[tokio::test]
+async fn routed_request_is_exported_and_propagated() {
+    let mut upstream = mockito::Server::new_async().await;
+    let song = upstream
+        .mock("GET", "/song/953953")
+        .match_header("traceparent", Matcher::Regex(format!("^00-{}-[0-9a-f]{{16}}-01$", TRACE_ID)))
+        .with_header("content-type", "application/json")
+        .with_body(
+            serde_json::json!({
+                "code": 0,
+                "msg": "",
+                "data": {
+                    "cid": "953953",
+                    "name": "Little Wish",
+                    "albumCid": "3888",
+                    "sourceUrl": null,
+                    "lyricUrl": null,
+                    "mvUrl": null,
+                    "mvCoverUrl": null,
+                    "artists": ["塞壬唱片-MSR"]
+                }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let mut collector = mockito::Server::new_async().await;
+    let exported = Arc::new(Mutex::new(Vec::new()));
+    let received = exported.clone();
+    let traces = collector
+        .mock("POST", "/v1/traces")
+        .with_body_from_request(move |request| {
+            received.lock().unwrap().extend_from_slice(request.body().unwrap());
+            Vec::new()
+        })
+        .expect_at_least(1)
+        .create_async()
+        .await;
+
+    let telemetry = easy_msr_api::telemetry::init(&collector.url(), "easy-msr-api-test").unwrap();
+    let subscriber = tracing_subscriber::registry().with(telemetry.layer());
+    let guard = tracing::subscriber::set_default(subscriber);
+
+    let app = web::routes_with_options(RemoteApiClient::new(upstream.url()), RouterOptions::default());
+    let request = Request::get("/song/953953")
+        .header("traceparent", format!("00-{}-00f067aa0ba902b7-01", TRACE_ID))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    song.assert_async().await;
+
+    drop(guard);
+    tokio::task::spawn_blocking(move || telemetry.shutdown()).await.unwrap();
+    traces.assert_async().await;
+
+    // OTLP/protobuf中的trace id为16字节的原始值
+    let trace_id: Vec<u8> = (0..TRACE_ID.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&TRACE_ID[i..i + 2], 16).unwrap())
+        .collect();
+    let exported = exported.lock().unwrap();
+    assert!(
+        exported.windows(trace_id.len()).any(|window| window == trace_id.as_slice()),
+        "导出的span不属于请求头中的链路"
+    );
+}
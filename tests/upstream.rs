@@ -0,0 +1,162 @@
+//! 多上游的故障切换、选择策略与健康检查
+
+use easy_msr_api::client::remote::RemoteApiClient;
+use easy_msr_api::client::upstream::{Upstream, UpstreamPolicy, track_upstreams};
+use std::time::Duration;
+
+fn song() -> String {
+    serde_json::json!({
+        "code": 0,
+        "msg": "",
+        "data": {
+            "cid": "953953",
+            "name": "Little Wish",
+            "albumCid": "3888",
+            "sourceUrl": null,
+            "lyricUrl": null,
+            "mvUrl": null,
+            "mvCoverUrl": null,
+            "artists": ["塞壬唱片-MSR"]
+        }
+    })
+    .to_string()
+}
+
+async fn serve_song(server: &mut mockito::Server, hits: usize) -> mockito::Mock {
+    server
+        .mock("GET", "/song/953953")
+        .with_header("content-type", "application/json")
+        .with_body(song())
+        .expect(hits)
+        .create_async()
+        .await
+}
+
+fn client(upstreams: Vec<Upstream>, policy: UpstreamPolicy) -> RemoteApiClient {
+    RemoteApiClient::with_upstreams(upstreams, policy, Duration::from_secs(5)).with_coalescing(false)
+}
+
+#[tokio::test]
+async fn failed_primary_is_skipped() {
+    let mut primary = mockito::Server::new_async().await;
+    let mut secondary = mockito::Server::new_async().await;
+    let failing = primary.mock("GET", "/song/953953").with_status(502).expect(1).create_async().await;
+    let served = serve_song(&mut secondary, 2).await;
+    let client = client(
+        vec![Upstream::new(primary.url()), Upstream::new(secondary.url())],
+        UpstreamPolicy::Priority,
+    );
+
+    let (song, upstreams) = track_upstreams(client.get_song("953953".into())).await;
+    assert!(song.is_ok());
+    assert_eq!(upstreams, vec![secondary.url()]);
+
+    // 失败的上游被暂时移出，下一个请求直接发往备用上游
+    let (song, upstreams) = track_upstreams(client.get_song("953953".into())).await;
+    assert!(song.is_ok());
+    assert_eq!(upstreams, vec![secondary.url()]);
+    failing.assert_async().await;
+    served.assert_async().await;
+
+    let status = client.upstream_status();
+    assert!(!status[0].healthy);
+    assert_eq!(status[0].failures, 1);
+    assert!(status[1].healthy);
+    assert_eq!(status[1].served, 2);
+}
+
+#[tokio::test]
+async fn client_errors_do_not_fail_over() {
+    let mut primary = mockito::Server::new_async().await;
+    let secondary = mockito::Server::new_async().await;
+    let missing = primary.mock("GET", "/song/0").with_status(404).expect(1).create_async().await;
+    let client = client(
+        vec![Upstream::new(primary.url()), Upstream::new(secondary.url())],
+        UpstreamPolicy::Priority,
+    );
+
+    assert!(client.get_song("0".into()).await.is_err());
+    missing.assert_async().await;
+    assert!(client.upstream_status()[0].healthy);
+}
+
+#[tokio::test]
+async fn priority_prefers_first_healthy_upstream() {
+    let mut primary = mockito::Server::new_async().await;
+    let mut secondary = mockito::Server::new_async().await;
+    let first = serve_song(&mut primary, 3).await;
+    let second = serve_song(&mut secondary, 0).await;
+    let client = client(
+        vec![Upstream::new(primary.url()), Upstream::new(secondary.url())],
+        UpstreamPolicy::Priority,
+    );
+
+    for _ in 0..3 {
+        client.get_song("953953".into()).await.unwrap();
+    }
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[tokio::test]
+async fn weighted_round_robin_follows_weights() {
+    let mut heavy = mockito::Server::new_async().await;
+    let mut light = mockito::Server::new_async().await;
+    let heavy_hits = serve_song(&mut heavy, 6).await;
+    let light_hits = serve_song(&mut light, 2).await;
+    let client = client(
+        vec![
+            Upstream {
+                base: heavy.url(),
+                weight: 3,
+            },
+            Upstream::new(light.url()),
+        ],
+        UpstreamPolicy::WeightedRoundRobin,
+    );
+
+    for _ in 0..8 {
+        client.get_song("953953".into()).await.unwrap();
+    }
+    heavy_hits.assert_async().await;
+    light_hits.assert_async().await;
+}
+
+#[tokio::test]
+async fn health_check_readmits_recovered_upstream() {
+    let mut primary = mockito::Server::new_async().await;
+    let mut secondary = mockito::Server::new_async().await;
+    let failing = primary.mock("GET", "/song/953953").with_status(500).expect(1).create_async().await;
+    let unhealthy = primary.mock("GET", "/fontset").with_status(503).create_async().await;
+    secondary.mock("GET", "/fontset").create_async().await;
+    serve_song(&mut secondary, 1).await;
+    let client = client(
+        vec![Upstream::new(primary.url()), Upstream::new(secondary.url())],
+        UpstreamPolicy::Priority,
+    )
+    .with_health_check(Duration::from_millis(50));
+
+    // 第一次请求时启动健康检查
+    let (_, upstreams) = track_upstreams(client.get_song("953953".into())).await;
+    assert_eq!(upstreams, vec![secondary.url()]);
+    assert!(!client.upstream_status()[0].healthy);
+    failing.assert_async().await;
+
+    // 主上游恢复后由健康检查重新加入
+    failing.remove_async().await;
+    unhealthy.remove_async().await;
+    primary.mock("GET", "/fontset").create_async().await;
+    let recovered = serve_song(&mut primary, 1).await;
+    let mut readmitted = false;
+    for _ in 0..40 {
+        tokio::time::sleep(Duration::from_millis(25)).await;
+        if client.upstream_status()[0].healthy {
+            readmitted = true;
+            break;
+        }
+    }
+    assert!(readmitted, "健康检查没有重新加入恢复的上游");
+    let (_, upstreams) = track_upstreams(client.get_song("953953".into())).await;
+    assert_eq!(upstreams, vec![primary.url()]);
+    recovered.assert_async().await;
+}